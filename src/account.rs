@@ -0,0 +1,66 @@
+use snarkvm_console_account::{compute_key::*, graph_key::*, private_key::*, view_key::*, Address};
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Field, Group};
+use snarkvm_console_types_scalar::{ensure, Result};
+
+use serde::{Deserialize, Serialize};
+
+use crate::keys::*;
+
+/// A multisig Aleo account whose sk_sig is threshold-shared among the signers
+///
+/// The account's compute key is (pk_sig, pr_sig) with pk_sig = group public key, so its address
+/// is the same as the address of the private key the shares were generated from.
+///
+/// The account holds only public values: no view key, r_sig or sk_prf. Since view_key =
+/// sk_sig + r_sig + sk_prf, anyone holding the view key together with r_sig and sk_prf can
+/// compute sk_sig. Transition view keys are combined from the signers' `NonceShares` instead.
+/// sk_tag is published in every signed request, it only links the account's record tags.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdAccount {
+    // The signer public keys and the group public key (pk_sig)
+    pub public_keys: PublicKeys,
+    // The account's pr_sig = G^r_sig
+    pub pr_sig: Group<TestnetV0>,
+    // The account's sk_tag, used to compute the tags of the records it spends
+    pub sk_tag: Field<TestnetV0>,
+}
+
+impl ThresholdAccount {
+    pub fn new(
+        public_keys: PublicKeys,
+        pr_sig: Group<TestnetV0>,
+        sk_tag: Field<TestnetV0>,
+    ) -> Self {
+        Self { public_keys, pr_sig, sk_tag }
+    }
+
+    /// Derive the threshold account of the private key the shares were generated from
+    ///
+    /// Run by the dealer at setup; only pr_sig and sk_tag are kept from the private key.
+    pub fn from_private_key(public_keys: PublicKeys, private_key: &PrivateKey<TestnetV0>) -> Result<Self> {
+        ensure!(
+            Network::g_scalar_multiply(&private_key.sk_sig()) == public_keys.group_public_key.0,
+            "The private key does not belong to the group public key"
+        );
+        let sk_tag = GraphKey::try_from(ViewKey::try_from(private_key)?)?.sk_tag();
+
+        Ok(Self::new(public_keys, Network::g_scalar_multiply(&private_key.r_sig()), sk_tag))
+    }
+
+    // The group public key used to verify the threshold signatures of the account
+    pub fn group_public_key(&self) -> &GroupPublicKey {
+        &self.public_keys.group_public_key
+    }
+
+    // The account's compute key (pk_sig, pr_sig)
+    pub fn compute_key(&self) -> Result<ComputeKey<TestnetV0>> {
+        ComputeKey::<TestnetV0>::try_from((self.public_keys.group_public_key.0, self.pr_sig))
+    }
+
+    // The account's address
+    pub fn address(&self) -> Result<Address<TestnetV0>> {
+        Address::<TestnetV0>::try_from(self.compute_key()?)
+    }
+}
@@ -0,0 +1,136 @@
+use snarkvm_console_account::{private_key::*, signature::*, Address};
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_program::{Identifier as FunctionName, ProgramID, Value, ValueType};
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{ensure, Result};
+
+use rand::Rng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    account::*,
+    generate_message::{bytes_to_fields, SigningMessage},
+    identifier::Identifier,
+    identity::*,
+};
+
+/// Domain separator for the hash of a signing target
+const TARGET_DOMAIN: &str = "AleoFrostTarget";
+
+/// Domain separator for the bytes signed in an operator request
+const OPERATOR_DOMAIN: &str = "AleoFrostOperator";
+
+/// A group registered with the coordinator
+///
+/// The coordinator holds the account to aggregate signatures and derive request transition keys,
+/// and the group config to authenticate the signers' envelopes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupRegistration {
+    pub group_id: String,
+    pub account: ThresholdAccount,
+    pub config: GroupConfig,
+}
+
+/// What a signing session asks the group to sign
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum SigningTarget {
+    Message(SigningMessage),
+    Call {
+        program_id: ProgramID<TestnetV0>,
+        function_name: FunctionName<TestnetV0>,
+        inputs: Vec<Value<TestnetV0>>,
+        input_types: Vec<ValueType<TestnetV0>>,
+    },
+}
+
+impl SigningTarget {
+    /// Hash the target, so the peers of a session can check they were asked to sign the same one
+    ///
+    /// HashPSD8(domain, JSON encoding of the target)
+    pub fn hash(&self) -> Result<Field<TestnetV0>> {
+        let mut preimage = vec![Field::<TestnetV0>::new_domain_separator(TARGET_DOMAIN)];
+        preimage.extend(bytes_to_fields(&serde_json::to_vec(self)?)?);
+
+        TestnetV0::hash_psd8(&preimage)
+    }
+}
+
+/// Opens a signing session for a registered group
+///
+/// The signing request is sent as soon as every signer has posted its commitment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenSession {
+    pub session_id: String,
+    pub group_id: String,
+    pub signers: Vec<Identifier>,
+    pub timeout_seconds: u64,
+    pub target: SigningTarget,
+}
+
+/// A group registration or session opening signed by the coordinator's identity key
+///
+/// Only whoever runs the coordinator may register groups or choose what the signers are asked
+/// to sign. The signature is over the domain and the exact JSON body with `sign_bytes`, so it
+/// cannot be mistaken for an envelope signed by the coordinator. A replayed request names a group
+/// or session that already exists and is refused.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OperatorRequest {
+    // The JSON encoding of the GroupRegistration or OpenSession
+    pub body: String,
+    // The coordinator identity's signature over the domain and the body
+    pub signature: Signature<TestnetV0>,
+}
+
+impl OperatorRequest {
+    // Sign a request with the coordinator's identity key
+    pub fn sign<T: Serialize, R: Rng>(request: &T, identity_key: &PrivateKey<TestnetV0>, rng: &mut R) -> Result<Self> {
+        let body = serde_json::to_string(request)?;
+        let signature = identity_key.sign_bytes(&operator_preimage(&body), rng)?;
+
+        Ok(Self { body, signature })
+    }
+
+    // Check the request is signed by the coordinator's identity
+    pub fn verify(&self, coordinator: &Address<TestnetV0>) -> Result<()> {
+        ensure!(
+            self.signature.to_address() == *coordinator,
+            "The request is signed by {}, not by the coordinator {coordinator}", self.signature.to_address()
+        );
+        ensure!(self.signature.verify_bytes(coordinator, &operator_preimage(&self.body)), "Invalid signature on the request");
+
+        Ok(())
+    }
+
+    // Decode the request after checking its signature, rejecting unknown fields
+    pub fn open<T: DeserializeOwned>(&self, coordinator: &Address<TestnetV0>) -> Result<T> {
+        self.verify(coordinator)?;
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+// The bytes signed in an operator request
+fn operator_preimage(body: &str) -> Vec<u8> {
+    [OPERATOR_DOMAIN.as_bytes(), body.as_bytes()].concat()
+}
+
+/// The public status of a signing session
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub group_id: String,
+    pub signers: Vec<Identifier>,
+    // The name of the coordinator's state
+    pub state: String,
+}
+
+/// The body of an error response
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiError {
+    pub error: String,
+}
@@ -0,0 +1,353 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use snarkvm_console_account::{private_key::*, signature::*, Address};
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{bail, ensure, Result, Zero};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{generate_message::bytes_to_fields, identifier::Identifier, keys::PublicKeys, policy::current_timestamp, preprocess::*};
+
+/// Domain separator for the audit entry hashes
+const AUDIT_DOMAIN: &str = "AleoFrostAuditLog";
+
+/// The protocol step an audit entry records
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditEventKind {
+    Keygen,
+    Preprocess,
+    PartialSignature,
+    Aggregation,
+}
+
+/// Whether the recorded step succeeded
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditOutcome {
+    Success,
+    // The step was refused or failed, with the reason
+    Failure(String),
+}
+
+impl AuditOutcome {
+    // The outcome of a step from its result
+    pub fn of<T>(result: &Result<T>) -> Self {
+        match result {
+            Ok(..) => Self::Success,
+            Err(error) => Self::Failure(error.to_string()),
+        }
+    }
+}
+
+/// A protocol step to be recorded in the audit log
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub kind: AuditEventKind,
+    // The signing or keygen session the step belongs to
+    pub session_id: String,
+    // The hash of the signed message, see `message_hash`
+    pub message_hash: Option<Field<TestnetV0>>,
+    // The participants taking part in the step
+    pub participants: Vec<Identifier>,
+    // The signing commitments used in the step
+    pub commitments: Vec<SigningCommitment>,
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEvent {
+    /// The keygen step that produced the group's public keys
+    ///
+    /// The message hash is the hash of the group public key, so the entry identifies the account.
+    pub fn keygen(keygen_id: &str, public_keys: &PublicKeys, outcome: AuditOutcome) -> Result<Self> {
+        let mut participants: Vec<Identifier> = public_keys.public_keys.keys().copied().collect();
+        participants.sort();
+
+        Ok(Self {
+            kind: AuditEventKind::Keygen,
+            session_id: keygen_id.to_string(),
+            message_hash: Some(message_hash(&[public_keys.group_public_key.0.to_x_coordinate()])?),
+            participants,
+            commitments: Vec::new(),
+            outcome,
+        })
+    }
+}
+
+/// The signed part of an audit entry
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    // The position of the entry in the log, starting at 0
+    pub sequence: u64,
+    // The unix timestamp the entry was written at
+    pub timestamp: u64,
+    // The hash of the previous entry, zero for the first entry
+    pub previous_hash: Field<TestnetV0>,
+    // The address of the Aleo key signing the entry
+    pub signer: Address<TestnetV0>,
+    pub event: AuditEvent,
+}
+
+impl AuditRecord {
+    // Hash the record as HashPSD8(domain, record JSON as fields)
+    pub fn hash(&self) -> Result<Field<TestnetV0>> {
+        let mut preimage = vec![Field::<TestnetV0>::new_domain_separator(AUDIT_DOMAIN)];
+        preimage.extend(bytes_to_fields(&serde_json::to_vec(self)?)?);
+
+        TestnetV0::hash_psd8(&preimage)
+    }
+}
+
+/// An entry of the audit log, one JSON line in the log file
+///
+/// Each entry commits to the previous one through `previous_hash` and is signed with the
+/// writer's Aleo key over [hash], so editing, removing or reordering entries breaks the chain.
+/// The entries are checked against the writer's known address, so the chain cannot be re-signed
+/// with another key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub record: AuditRecord,
+    // The hash of the record
+    pub hash: Field<TestnetV0>,
+    // The signer's signature over [hash]
+    pub signature: Signature<TestnetV0>,
+}
+
+impl AuditEntry {
+    // Check the entry's hash and its signature by the expected signer
+    pub fn verify(&self, signer: &Address<TestnetV0>) -> Result<()> {
+        ensure!(
+            self.record.signer == *signer,
+            "Audit entry {} is signed by {}, expected {signer}", self.record.sequence, self.record.signer
+        );
+        ensure!(self.record.hash()? == self.hash, "Audit entry {} does not match its hash", self.record.sequence);
+        ensure!(
+            self.signature.verify(&self.record.signer, &[self.hash]),
+            "Audit entry {} has an invalid signature from {}", self.record.sequence, self.record.signer
+        );
+
+        Ok(())
+    }
+}
+
+/// An append-only audit log of the protocol steps taken by one signer
+pub struct AuditLog {
+    path: PathBuf,
+    // The address of the key every entry is signed with
+    signer: Address<TestnetV0>,
+    // The number of entries in the log
+    length: u64,
+    // The hash of the last entry
+    head: Field<TestnetV0>,
+}
+
+impl AuditLog {
+    // Open the log of the signer at the path, verifying the existing entries, or start a new log
+    pub fn open<P: AsRef<Path>>(path: P, signer: Address<TestnetV0>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (length, head) = match path.exists() {
+            true => {
+                let entries = read_audit_log(&path)?;
+                let head = verify_audit_log(&entries, &signer, None)?;
+                (entries.len() as u64, head)
+            }
+            false => (0, Field::<TestnetV0>::zero()),
+        };
+
+        Ok(Self { path, signer, length, head })
+    }
+
+    // The hash of the last entry, to be kept elsewhere to detect truncation of the log
+    pub fn head(&self) -> Field<TestnetV0> {
+        self.head
+    }
+
+    // Sign the event with the Aleo key and append it to the log
+    pub fn append<R: Rng>(&mut self, private_key: &PrivateKey<TestnetV0>, event: AuditEvent, rng: &mut R) -> Result<AuditEntry> {
+        let signer = Address::try_from(private_key)?;
+        ensure!(signer == self.signer, "The audit log is signed by {}, not {signer}", self.signer);
+
+        let record = AuditRecord { sequence: self.length, timestamp: current_timestamp()?, previous_hash: self.head, signer, event };
+        let hash = record.hash()?;
+        let entry = AuditEntry { record, hash, signature: private_key.sign(&[hash], rng)? };
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.sync_all()?;
+
+        self.length += 1;
+        self.head = hash;
+
+        Ok(entry)
+    }
+}
+
+/// An audit log together with the Aleo key its entries are signed with
+///
+/// Held by the party running the protocol steps, e.g. the signer daemon with its identity key.
+pub struct Auditor {
+    log: AuditLog,
+    private_key: PrivateKey<TestnetV0>,
+}
+
+impl Auditor {
+    // Open the log at the path, signing new entries with the private key
+    pub fn open<P: AsRef<Path>>(path: P, private_key: PrivateKey<TestnetV0>) -> Result<Self> {
+        Ok(Self { log: AuditLog::open(path, Address::try_from(&private_key)?)?, private_key })
+    }
+
+    // The hash of the last entry
+    pub fn head(&self) -> Field<TestnetV0> {
+        self.log.head()
+    }
+
+    // The number of entries in the log
+    pub fn len(&self) -> u64 {
+        self.log.length
+    }
+
+    // Check whether the log has no entries yet
+    pub fn is_empty(&self) -> bool {
+        self.log.length == 0
+    }
+
+    // Sign the event and append it to the log
+    pub fn record<R: Rng>(&mut self, event: AuditEvent, rng: &mut R) -> Result<AuditEntry> {
+        self.log.append(&self.private_key, event, rng)
+    }
+}
+
+// Hash a signed message for the audit log as HashPSD8(message)
+pub fn message_hash(message: &[Field<TestnetV0>]) -> Result<Field<TestnetV0>> {
+    TestnetV0::hash_psd8(message)
+}
+
+// Read the entries of an audit log file
+pub fn read_audit_log<P: AsRef<Path>>(path: P) -> Result<Vec<AuditEntry>> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut entries = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => bail!("Malformed audit entry on line {}: {error}", line_number + 1),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Verify the chain of audit entries signed by the signer, returning the hash of the last entry
+///
+/// Detects any edited, inserted, removed or reordered entry, and a chain re-signed with another
+/// key. Removing entries from the end is only detected when the expected head hash, kept
+/// outside of the log, is given.
+pub fn verify_audit_log(
+    entries: &[AuditEntry],
+    signer: &Address<TestnetV0>,
+    expected_head: Option<Field<TestnetV0>>,
+) -> Result<Field<TestnetV0>> {
+    let mut head = Field::<TestnetV0>::zero();
+    for (sequence, entry) in entries.iter().enumerate() {
+        ensure!(entry.record.sequence == sequence as u64, "Expected audit entry {sequence}, found entry {}", entry.record.sequence);
+        ensure!(entry.record.previous_hash == head, "Audit entry {sequence} does not follow the previous entry");
+        entry.verify(signer)?;
+
+        head = entry.hash;
+    }
+
+    if let Some(expected_head) = expected_head {
+        ensure!(head == expected_head, "The audit log does not end at the expected entry");
+    }
+
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    // A log of three entries signed with the key, and its entries
+    fn audit_log(private_key: &PrivateKey<TestnetV0>, rng: &mut TestRng) -> (AuditLog, Vec<AuditEntry>) {
+        let path = std::env::temp_dir().join(format!("aleo-frost-audit-{}.jsonl", rng.gen::<u64>()));
+        let mut log = AuditLog::open(&path, Address::try_from(private_key).unwrap()).unwrap();
+        for kind in [AuditEventKind::Preprocess, AuditEventKind::PartialSignature, AuditEventKind::Aggregation] {
+            let event = AuditEvent {
+                kind,
+                session_id: "session-1".to_string(),
+                message_hash: None,
+                participants: Vec::new(),
+                commitments: Vec::new(),
+                outcome: AuditOutcome::Success,
+            };
+            log.append(private_key, event, rng).unwrap();
+        }
+        let entries = read_audit_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        (log, entries)
+    }
+
+    #[test]
+    fn test_audit_log_verifies() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let signer = Address::try_from(&private_key).unwrap();
+        let (log, entries) = audit_log(&private_key, rng);
+
+        assert_eq!(verify_audit_log(&entries, &signer, Some(log.head())).unwrap(), log.head());
+        // A truncated log is only detected with the expected head
+        assert!(verify_audit_log(&entries[..2], &signer, None).is_ok());
+        assert!(verify_audit_log(&entries[..2], &signer, Some(log.head())).is_err());
+    }
+
+    #[test]
+    fn test_edited_entry_is_rejected() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (_, mut entries) = audit_log(&private_key, rng);
+
+        entries[1].record.event.outcome = AuditOutcome::Failure("edited".to_string());
+        assert!(verify_audit_log(&entries, &Address::try_from(&private_key).unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_deleted_entry_is_rejected() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (_, mut entries) = audit_log(&private_key, rng);
+
+        entries.remove(1);
+        assert!(verify_audit_log(&entries, &Address::try_from(&private_key).unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_resigned_chain_is_rejected() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (_, entries) = audit_log(&private_key, rng);
+
+        // Rebuild the whole chain with an edited entry under another key
+        let forger = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let mut forged = Vec::with_capacity(entries.len());
+        let mut head = Field::<TestnetV0>::zero();
+        for entry in &entries {
+            let mut record = entry.record.clone();
+            record.previous_hash = head;
+            record.signer = Address::try_from(&forger).unwrap();
+            record.event.outcome = AuditOutcome::Failure("edited".to_string());
+            let hash = record.hash().unwrap();
+            forged.push(AuditEntry { record, hash, signature: forger.sign(&[hash], rng).unwrap() });
+            head = hash;
+        }
+
+        // The forged chain is consistent on its own but not signed by the expected signer
+        assert!(verify_audit_log(&forged, &Address::try_from(&forger).unwrap(), None).is_ok());
+        assert!(verify_audit_log(&forged, &Address::try_from(&private_key).unwrap(), None).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use snarkvm_circuit_network::AleoTestnetV0;
+use snarkvm_console_account::PrivateKey;
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::{Identifier as FunctionName, ProgramID, Value, ValueType};
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{bail, Result};
+use snarkvm_synthesizer_process::{Authorization, CallStack, Process};
+use snarkvm_synthesizer_program::Instruction;
+
+use rand::{CryptoRng, Rng};
+use std::{path::Path, str::FromStr};
+
+use crate::{account::*, frost::*, preprocess::*, request::*};
+
+/// Build the unsigned authorization of a `credits.aleo` call from the threshold account
+///
+/// The returned package is what the signers threshold-sign, see `RequestSigningPackage`.
+pub fn credits_signing_package(
+    function_name: &str,
+    inputs: &[&str],
+    input_types: &[&str],
+    signing_commitments: Vec<SigningCommitment>,
+) -> Result<RequestSigningPackage> {
+    RequestSigningPackage::new(
+        ProgramID::from_str("credits.aleo")?,
+        FunctionName::from_str(function_name)?,
+        inputs.iter().map(|input| Value::from_str(input)).collect::<Result<_>>()?,
+        input_types.iter().map(|input_type| ValueType::from_str(input_type)).collect::<Result<_>>()?,
+        signing_commitments,
+    )
+}
+
+/// Build the unsigned authorization of the `credits.aleo/fee_public` transition paying for an execution
+///
+/// `execution_id` - The id of the execution authorization, see `Authorization::to_execution_id`.
+pub fn fee_public_signing_package(
+    base_fee_in_microcredits: u64,
+    priority_fee_in_microcredits: u64,
+    execution_id: Field<TestnetV0>,
+    signing_commitments: Vec<SigningCommitment>,
+) -> Result<RequestSigningPackage> {
+    credits_signing_package(
+        "fee_public",
+        &[&format!("{base_fee_in_microcredits}u64"), &format!("{priority_fee_in_microcredits}u64"), &execution_id.to_string()],
+        &["u64.public", "u64.public", "field.public"],
+        signing_commitments,
+    )
+}
+
+/// Aggregate the partial signatures of a signing package and build the `Authorization` of the call
+///
+/// The transition is computed by running the function in authorize mode, which needs the signed
+/// request but no private key. Functions making external calls would need the threshold account
+/// to sign the child requests as well, so they are rejected.
+pub fn threshold_authorize<R: Rng + CryptoRng>(
+    process: &Process<TestnetV0>,
+    package: &RequestSigningPackage,
+    account: &ThresholdAccount,
+    partial_signatures: &[PartialThresholdSignature],
+    rng: &mut R,
+) -> Result<Authorization<TestnetV0>> {
+    let stack = process.get_stack(package.program_id)?;
+
+    let function = stack.program().get_function(&package.function_name)?;
+    if function.instructions().iter().any(|instruction| matches!(instruction, Instruction::Call(..))) {
+        bail!("'{}/{}' makes external calls, which threshold authorization does not support", package.program_id, package.function_name);
+    }
+
+    let request = package.aggregate(account, partial_signatures)?;
+    let authorization = Authorization::new(request.clone());
+
+    // The call stack requires a private key to sign the requests of external calls, which were ruled
+    // out above, so a throwaway key is used and never signs anything
+    let unused_private_key = PrivateKey::<TestnetV0>::new(rng)?;
+    let call_stack = CallStack::Authorize(vec![request], unused_private_key, authorization.clone());
+    stack.execute_function::<AleoTestnetV0, R>(call_stack, None, rng)?;
+
+    Ok(authorization)
+}
+
+// Write a finished authorization to disk as JSON, to be executed by an online prover
+pub fn save_authorization<P: AsRef<Path>>(authorization: &Authorization<TestnetV0>, path: P) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(authorization)?)?;
+    Ok(())
+}
+
+// Read an authorization from a JSON file on disk
+pub fn load_authorization<P: AsRef<Path>>(path: P) -> Result<Authorization<TestnetV0>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::keys::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    const RECIPIENT: &str = "aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew";
+
+    // Run the nonce share and signing rounds of a package for the signers and authorize the call
+    fn authorize(
+        process: &Process<TestnetV0>,
+        mut package: RequestSigningPackage,
+        nonces: &[SigningNonce],
+        account: &ThresholdAccount,
+        signers: &[&SignerShare],
+        rng: &mut TestRng,
+    ) -> Authorization<TestnetV0> {
+        for (share, nonce) in signers.iter().zip(nonces) {
+            let nonce_shares = package.nonce_shares_for(account, share, nonce, rng).unwrap();
+            package.add_nonce_shares(nonce_shares).unwrap();
+        }
+        let partial_signatures: Vec<PartialThresholdSignature> =
+            signers.iter().zip(nonces).map(|(share, nonce)| package.partial_sign(account, share, nonce).unwrap()).collect();
+
+        threshold_authorize(process, &package, account, &partial_signatures, rng).unwrap()
+    }
+
+    #[test]
+    fn test_transfer_and_fee_round_trip_through_json() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+        let signers = [&shares[0], &shares[2]];
+        let process = Process::<TestnetV0>::load().unwrap();
+
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|share| preprocess(1, share.participant_index, rng)).map(|(n, c)| (n[0], c[0])).unzip();
+        let package =
+            credits_signing_package("transfer_public", &[RECIPIENT, "10u64"], &["address.public", "u64.public"], commitments).unwrap();
+        let authorization = authorize(&process, package, &nonces, &account, &signers, rng);
+        assert_eq!(authorization.len(), 1);
+
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|share| preprocess(1, share.participant_index, rng)).map(|(n, c)| (n[0], c[0])).unzip();
+        let package = fee_public_signing_package(10_000, 0, authorization.to_execution_id().unwrap(), commitments).unwrap();
+        let fee_authorization = authorize(&process, package, &nonces, &account, &signers, rng);
+        assert_eq!(fee_authorization.len(), 1);
+
+        for authorization in [authorization, fee_authorization] {
+            let path = std::env::temp_dir().join(format!("aleo-frost-authorization-{}.json", rng.gen::<u64>()));
+            save_authorization(&authorization, &path).unwrap();
+            let loaded = load_authorization(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded.to_string(), authorization.to_string());
+            assert_eq!(loaded.to_execution_id().unwrap(), authorization.to_execution_id().unwrap());
+            for (loaded, transition) in loaded.transitions().values().zip(authorization.transitions().values()) {
+                assert_eq!(loaded.id(), transition.id());
+            }
+            for request in loaded.to_vec_deque() {
+                assert_eq!(*request.signer(), account.address().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsigned_call_and_broken_file_are_refused() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+        let process = Process::<TestnetV0>::load().unwrap();
+
+        // A truncated or edited file is not an authorization
+        let path = std::env::temp_dir().join(format!("aleo-frost-authorization-{}.json", rng.gen::<u64>()));
+        std::fs::write(&path, "{\"requests\": []").unwrap();
+        assert!(load_authorization(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        // Signing without any partial signature is refused before the function runs
+        let (_, commitments) = preprocess(1, shares[0].participant_index, rng);
+        let package = credits_signing_package("transfer_public", &[RECIPIENT, "10u64"], &["address.public", "u64.public"], commitments).unwrap();
+        assert!(threshold_authorize(&process, &package, &account, &[], rng).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+// Coordinator service collecting commitments and partial signatures over HTTP/JSON
+//
+// POST /groups                         register a group (OperatorRequest of a GroupRegistration)
+// POST /sessions                       open a signing session (OperatorRequest of an OpenSession)
+// GET  /sessions?participant=<id>      list the open sessions of a participant
+// POST /sessions/<id>/commitments      post a signer's commitment (SignedEnvelope)
+// POST /sessions/<id>/nonce-shares     post a signer's nonce shares (SignedEnvelope)
+// POST /sessions/<id>/partials         post a signer's partial signature (SignedEnvelope)
+// GET  /sessions/<id>/messages         every envelope sent by the coordinator in the session
+// GET  /sessions/<id>/package          the signing package
+// GET  /sessions/<id>/result           the aggregated signature or request
+//
+// Every partial signature received and every step taken is recorded in the coordinator's audit log.
+//
+// Groups and sessions are only registered and opened by operator requests signed with the
+// coordinator's own identity key. Request bodies are limited to MAX_BODY_BYTES.
+//
+// Unknown groups and sessions are answered with 404, duplicates with 409, envelopes and operator
+// requests failing authentication with 401, oversized bodies with 413 and failures of the
+// coordinator itself with 500; any other error is a 400.
+//
+// usage: coordinator <identity private key> [listen address, default 127.0.0.1:8080] [audit log, default coordinator-audit.jsonl]
+use std::{collections::HashMap, fmt, io::Read, str::FromStr};
+
+use snarkvm_console_account::{private_key::*, Address};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types_scalar::{bail, ensure, Error, Result};
+
+use rand::rngs::OsRng;
+use serde::{de::DeserializeOwned, Serialize};
+use tiny_http::{Header, Method, Response, Server};
+
+use aleo_frost::*;
+
+// The largest request body read, in bytes
+const MAX_BODY_BYTES: u64 = 1 << 20;
+
+// A signing session and everything the coordinator sent in it
+struct SessionEntry {
+    group_id: String,
+    target: SigningTarget,
+    session: CoordinatorSession,
+    outbox: Vec<SignedEnvelope>,
+}
+
+struct Coordinator {
+    identity_key: PrivateKey<TestnetV0>,
+    groups: HashMap<String, GroupRegistration>,
+    sessions: HashMap<String, SessionEntry>,
+    auditor: Auditor,
+}
+
+// A JSON response with its status code
+type Reply = (u16, String);
+
+// An error answered with its own status code instead of 400
+#[derive(Debug)]
+struct StatusError {
+    status: u16,
+    message: String,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+fn status_error(status: u16, message: impl ToString) -> Error {
+    Error::new(StatusError { status, message: message.to_string() })
+}
+
+// The status code to answer an error with
+fn status_of(error: &Error) -> u16 {
+    error.downcast_ref::<StatusError>().map_or(400, |error| error.status)
+}
+
+fn json<T: Serialize>(status: u16, body: &T) -> Result<Reply> {
+    Ok((status, serde_json::to_string(body)?))
+}
+
+impl Coordinator {
+    fn handle(&mut self, method: &Method, url: &str, body: &str) -> Result<Reply> {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        self.expire_sessions()?;
+
+        match (method, segments.as_slice()) {
+            (Method::Post, ["groups"]) => self.register_group(self.operator_request(body)?),
+            (Method::Post, ["sessions"]) => self.open_session(self.operator_request(body)?),
+            (Method::Get, ["sessions"]) => self.list_sessions(query),
+            (Method::Post, ["sessions", session_id, "commitments" | "nonce-shares" | "partials"]) => {
+                self.receive(session_id, serde_json::from_str(body)?)
+            }
+            (Method::Get, ["sessions", session_id, "messages"]) => json(200, &self.entry(session_id)?.outbox),
+            (Method::Get, ["sessions", session_id, "package"]) => self.find_message(session_id, |payload| matches!(payload, Payload::SigningRequest(..))),
+            (Method::Get, ["sessions", session_id, "result"]) => self.find_message(session_id, |payload| matches!(payload, Payload::AggregationResult(..))),
+            _ => json(404, &ApiError { error: format!("No route for {method} {path}") }),
+        }
+    }
+
+    // Decode an operator request after checking it is signed by the coordinator's identity
+    fn operator_request<T: DeserializeOwned>(&self, body: &str) -> Result<T> {
+        let request: OperatorRequest = serde_json::from_str(body)?;
+        request.verify(&Address::try_from(&self.identity_key)?).map_err(|error| status_error(401, error))?;
+
+        Ok(serde_json::from_str(&request.body)?)
+    }
+
+    fn register_group(&mut self, registration: GroupRegistration) -> Result<Reply> {
+        if self.groups.contains_key(&registration.group_id) {
+            return Err(status_error(409, format!("Group '{}' is already registered", registration.group_id)));
+        }
+        ensure!(
+            registration.config.coordinator == Address::try_from(&self.identity_key)?,
+            "The group config registers another coordinator identity"
+        );
+        // Sessions address signers by participant id, so every participant must own exactly its own share index
+        let public_keys = &registration.account.public_keys;
+        for participant_index in public_keys.public_keys.keys() {
+            ensure!(
+                public_keys.participant_shares.get(participant_index) == Some(&vec![*participant_index]),
+                "Weighted groups are not supported by the coordinator, share {participant_index} is not owned by participant {participant_index}"
+            );
+            registration.config.identity(&Party::Participant(*participant_index))?;
+        }
+        ensure!(
+            public_keys.participant_shares.len() == public_keys.public_keys.len(),
+            "Weighted groups are not supported by the coordinator"
+        );
+
+        let group_id = registration.group_id.clone();
+        self.groups.insert(group_id.clone(), registration);
+        json(201, &group_id)
+    }
+
+    fn open_session(&mut self, open: OpenSession) -> Result<Reply> {
+        if self.sessions.contains_key(&open.session_id) {
+            return Err(status_error(409, format!("Session '{}' already exists", open.session_id)));
+        }
+        let group = match self.groups.get(&open.group_id) {
+            Some(group) => group,
+            None => return Err(status_error(404, format!("Group '{}' is not registered", open.group_id))),
+        };
+        for signer in &open.signers {
+            ensure!(group.account.public_keys.public_keys.contains_key(signer), "Participant {signer} is not part of group '{}'", open.group_id);
+        }
+
+        let session = CoordinatorSession::new(&open.session_id, open.signers, open.timeout_seconds)?;
+        let info = session_info(&open.group_id, &session);
+        self.sessions.insert(open.session_id, SessionEntry { group_id: open.group_id, target: open.target, session, outbox: Vec::new() });
+
+        json(201, &info)
+    }
+
+    fn list_sessions(&mut self, query: &str) -> Result<Reply> {
+        let participant = match query.strip_prefix("participant=") {
+            Some(participant) => Some(Identifier::from_str(participant)?),
+            None => None,
+        };
+
+        let mut sessions: Vec<SessionInfo> = self
+            .sessions
+            .values()
+            .filter(|entry| participant.map_or(true, |participant| entry.session.signers.contains(&participant)))
+            .filter(|entry| !matches!(entry.session.state, CoordinatorState::Complete { .. } | CoordinatorState::Aborted { .. }))
+            .map(|entry| session_info(&entry.group_id, &entry.session))
+            .collect();
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+        json(200, &sessions)
+    }
+
+    // Abort the sessions past their deadline, sending the abort to their signers
+    fn expire_sessions(&mut self) -> Result<()> {
+        for entry in self.sessions.values_mut() {
+            let mut event = entry.session.audit_event(AuditEventKind::Aggregation, AuditOutcome::Success)?;
+            if let Some(abort) = entry.session.expire()? {
+                let signed = SignedEnvelope::sign(&abort, &self.identity_key, &mut OsRng).map_err(|error| status_error(500, error))?;
+                entry.outbox.push(signed);
+                event.outcome = AuditOutcome::Failure("The session timed out".to_string());
+                self.auditor.record(event, &mut OsRng).map_err(|error| status_error(500, error))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn entry(&self, session_id: &str) -> Result<&SessionEntry> {
+        match self.sessions.get(session_id) {
+            Some(entry) => Ok(entry),
+            None => Err(status_error(404, format!("Session '{session_id}' does not exist"))),
+        }
+    }
+
+    // The latest envelope sent by the coordinator with a matching payload
+    fn find_message(&self, session_id: &str, matches: impl Fn(&Payload) -> bool) -> Result<Reply> {
+        let entry = self.entry(session_id)?;
+        for signed in entry.outbox.iter().rev() {
+            if matches(&Envelope::decode(signed.envelope.as_bytes())?.payload) {
+                return json(200, signed);
+            }
+        }
+
+        json(404, &ApiError { error: format!("Session '{session_id}' has no such message yet") })
+    }
+
+    /// Authenticate a signer's envelope and advance the session
+    ///
+    /// The signing request is sent once every commitment is in, resent with the nonce shares
+    /// once all of them are in, and the signature is aggregated once every partial signature is in.
+    fn receive(&mut self, session_id: &str, signed: SignedEnvelope) -> Result<Reply> {
+        let entry = match self.sessions.get_mut(session_id) {
+            Some(entry) => entry,
+            None => return Err(status_error(404, format!("Session '{session_id}' does not exist"))),
+        };
+        let group = match self.groups.get(&entry.group_id) {
+            Some(group) => group,
+            None => return Err(status_error(500, format!("Group '{}' of session '{session_id}' is not registered", entry.group_id))),
+        };
+
+        let envelope = signed.open(&group.config).map_err(|error| status_error(401, error))?;
+        let received = entry.session.receive(&envelope);
+        if let (Payload::PartialSignature(..), Party::Participant(sender)) = (&envelope.payload, envelope.sender) {
+            let mut event = entry.session.audit_event(AuditEventKind::PartialSignature, AuditOutcome::of(&received))?;
+            event.participants = vec![sender];
+            self.auditor.record(event, &mut OsRng).map_err(|error| status_error(500, error))?;
+        }
+        received?;
+
+        // The event is built before the step, while the session still holds the package
+        let kind = match &entry.session.state {
+            CoordinatorState::CollectingSignatures { .. } => AuditEventKind::Aggregation,
+            _ => AuditEventKind::Preprocess,
+        };
+        let mut event = entry.session.audit_event(kind, AuditOutcome::Success)?;
+
+        let signers = entry.session.signers.len();
+        let reply = match entry.session.state {
+            CoordinatorState::CollectingCommitments if entry.session.commitments.len() == signers => Some(match entry.target.clone() {
+                SigningTarget::Message(message) => entry.session.request_message(message),
+                SigningTarget::Call { program_id, function_name, inputs, input_types } => {
+                    entry.session.request_call(program_id, function_name, inputs, input_types)
+                }
+            }),
+            CoordinatorState::CollectingNonceShares { .. } if entry.session.nonce_senders.len() == signers => {
+                Some(entry.session.request_with_nonce_shares())
+            }
+            CoordinatorState::CollectingSignatures { .. } if entry.session.partial_signatures.len() == signers => {
+                Some(entry.session.aggregate(&group.account))
+            }
+            _ => None,
+        };
+
+        if let Some(reply) = reply {
+            let reply = match reply {
+                Ok(reply) => reply,
+                Err(error) => entry.session.abort(&error.to_string())?,
+            };
+            let signed = SignedEnvelope::sign(&reply, &self.identity_key, &mut OsRng).map_err(|error| status_error(500, error))?;
+            entry.outbox.push(signed);
+
+            if let CoordinatorState::Aborted { reason } = &entry.session.state {
+                event.outcome = AuditOutcome::Failure(reason.clone());
+            }
+            self.auditor.record(event, &mut OsRng).map_err(|error| status_error(500, error))?;
+        }
+
+        json(200, &session_info(&entry.group_id, &entry.session))
+    }
+}
+
+fn session_info(group_id: &str, session: &CoordinatorSession) -> SessionInfo {
+    SessionInfo {
+        session_id: session.session_id.clone(),
+        group_id: group_id.to_string(),
+        signers: session.signers.clone(),
+        state: session.state.name().to_string(),
+    }
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let identity_key = match args.next() {
+        Some(identity_key) => PrivateKey::<TestnetV0>::from_str(&identity_key)?,
+        None => bail!("usage: coordinator <identity private key> [listen address] [audit log]"),
+    };
+    let address = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let auditor = Auditor::open(args.next().unwrap_or_else(|| "coordinator-audit.jsonl".to_string()), identity_key)?;
+
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(error) => bail!("Cannot listen on {address}: {error}"),
+    };
+    println!("coordinator {} listening on {address}", Address::try_from(&identity_key)?);
+
+    let mut coordinator = Coordinator { identity_key, groups: HashMap::new(), sessions: HashMap::new(), auditor };
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, body) = match request.as_reader().take(MAX_BODY_BYTES + 1).read_to_string(&mut body) {
+            Ok(length) if length as u64 > MAX_BODY_BYTES => Err(status_error(413, format!("Request bodies are limited to {MAX_BODY_BYTES} bytes"))),
+            Ok(..) => coordinator.handle(request.method(), request.url(), &body),
+            Err(error) => Err(error.into()),
+        }
+        .unwrap_or_else(|error| (status_of(&error), serde_json::to_string(&ApiError { error: error.to_string() }).unwrap_or_default()));
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").expect("a valid header"));
+        if let Err(error) = request.respond(response) {
+            eprintln!("failed to respond: {error}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+// Signer daemon polling a coordinator and signing its sessions per the signer's policy
+//
+// Every poll the daemon tops up its nonce pool, lists the coordinator's open sessions for its
+// participant, announces a commitment for new sessions, and answers the coordinator's signing
+// requests with nonce shares or a partial signature once the request passes the clear-signing
+// and policy checks. Every step is recorded in the signer's audit log, signed with its identity key.
+//
+// usage: signer <config file>, with the keystore password in ALEO_FROST_PASSWORD
+use std::{collections::HashSet, path::PathBuf, thread, time::Duration};
+
+use snarkvm_console_account::{private_key::*, Address};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+
+use aleo_frost::*;
+
+/// The configuration of a signer daemon
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignerConfig {
+    // The coordinator's base url, e.g. "http://127.0.0.1:8080"
+    coordinator_url: String,
+    // The group's threshold account
+    account: ThresholdAccount,
+    // The group's identity keys
+    group: GroupConfig,
+    // The keystore holding the key share and the identity key registered in the group config
+    keystore: PathBuf,
+    // The signer's policy
+    policy: PathBuf,
+    // The directory holding the spending ledger, nonce pool and sessions
+    state_dir: PathBuf,
+    #[serde(default = "default_nonce_pool_size")]
+    nonce_pool_size: usize,
+    #[serde(default = "default_poll_seconds")]
+    poll_seconds: u64,
+    // The signer aborts its side of a session after this many seconds
+    #[serde(default = "default_session_timeout_seconds")]
+    session_timeout_seconds: u64,
+}
+
+fn default_nonce_pool_size() -> usize {
+    16
+}
+
+fn default_poll_seconds() -> u64 {
+    5
+}
+
+fn default_session_timeout_seconds() -> u64 {
+    600
+}
+
+struct Signer {
+    config: SignerConfig,
+    identity_key: PrivateKey<TestnetV0>,
+    share: SignerShare,
+    policy: SigningPolicy,
+    ledger: SpendingLedger,
+    nonce_pool: NoncePool,
+    auditor: Auditor,
+    // The sessions whose last envelope reached the coordinator since the daemon started
+    sent: HashSet<String>,
+}
+
+impl Signer {
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.config.coordinator_url.trim_end_matches('/'), path)
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        match ureq::get(&self.url(path)).call() {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(status, response)) => bail!("GET {path} failed with {status}: {}", response.into_string()?),
+            Err(error) => bail!("GET {path} failed: {error}"),
+        }
+    }
+
+    // Sign an envelope with the identity key and post it to the coordinator
+    fn post(&self, path: &str, envelope: &Envelope) -> Result<()> {
+        let signed = SignedEnvelope::sign(envelope, &self.identity_key, &mut OsRng)?;
+        match ureq::post(&self.url(path)).set("Content-Type", "application/json").send_bytes(&signed.encode()?) {
+            Ok(..) => Ok(()),
+            Err(ureq::Error::Status(status, response)) => bail!("POST {path} failed with {status}: {}", response.into_string()?),
+            Err(error) => bail!("POST {path} failed: {error}"),
+        }
+    }
+
+    // Post a session's envelope to its route, remembering whether it reached the coordinator
+    fn send(&mut self, session_id: &str, envelope: &Envelope) -> Result<()> {
+        let route = match envelope.payload {
+            Payload::CommitmentAnnouncement(..) => "commitments",
+            Payload::NonceShares(..) => "nonce-shares",
+            Payload::PartialSignature(..) => "partials",
+            ref payload => bail!("Signers do not send a {}", payload.name()),
+        };
+
+        self.sent.remove(session_id);
+        self.post(&format!("sessions/{session_id}/{route}"), envelope)?;
+        self.sent.insert(session_id.to_string());
+        Ok(())
+    }
+
+    fn session_path(&self, session_id: &str) -> Result<PathBuf> {
+        if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            bail!("Refusing session id '{session_id}'");
+        }
+        Ok(self.config.state_dir.join(format!("session-{session_id}.json")))
+    }
+
+    fn poll(&mut self) -> Result<()> {
+        self.nonce_pool.top_up(self.config.nonce_pool_size, &mut OsRng)?;
+
+        let participant_index = self.share.participant_index;
+        let sessions: Vec<SessionInfo> = serde_json::from_str(&self.get(&format!("sessions?participant={participant_index}"))?)?;
+        for info in sessions {
+            if let Err(error) = self.advance(&info.session_id) {
+                eprintln!("session '{}': {error}", info.session_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Take the next step in a session
+    fn advance(&mut self, session_id: &str) -> Result<()> {
+        let path = self.session_path(session_id)?;
+        if !path.exists() {
+            let nonce = self.nonce_pool.take()?;
+            let mut session = SignerSession::from_nonce(&path, session_id, self.share.participant_index, nonce, self.config.session_timeout_seconds)?;
+            let announced = session.announce();
+            self.auditor.record(session.audit_event(AuditEventKind::Preprocess, None, AuditOutcome::of(&announced))?, &mut OsRng)?;
+            return self.send(session_id, &announced?);
+        }
+
+        let mut session = SignerSession::resume(&path)?;
+        // The last envelope may not have reached the coordinator if its POST failed or the daemon restarted
+        let open = matches!(session.state, SignerState::Committed { .. } | SignerState::Signed { .. });
+        if open && !self.sent.contains(session_id) {
+            self.send(session_id, &session.resend()?)?;
+        }
+        if !matches!(session.state, SignerState::Committed { .. }) {
+            return Ok(());
+        }
+
+        let messages: Vec<SignedEnvelope> = serde_json::from_str(&self.get(&format!("sessions/{session_id}/messages"))?)?;
+        for signed in messages {
+            let envelope = signed.open(&self.config.group)?;
+            if session.last_received.map_or(false, |last| envelope.sequence <= last) {
+                continue;
+            }
+
+            let received = session.receive(&envelope);
+            if let Payload::AggregationResult(result) = &envelope.payload {
+                let outcome = match &**result {
+                    AggregationResult::Failure(reason) => AuditOutcome::Failure(reason.clone()),
+                    _ => AuditOutcome::Success,
+                };
+                self.auditor.record(session.audit_event(AuditEventKind::Aggregation, None, outcome)?, &mut OsRng)?;
+            }
+            let request = match received? {
+                Some(request) => request,
+                None => continue,
+            };
+
+            // Nonce shares come first for requests
+            if let SigningRequest::Request(package) = &request {
+                if !package.has_nonce_shares() {
+                    let envelope = session.nonce_shares(package, &self.config.account, &self.share, &mut OsRng)?;
+                    self.send(session_id, &envelope)?;
+                    continue;
+                }
+            }
+
+            let summary = match &request {
+                SigningRequest::Message(package) => package.summary()?,
+                SigningRequest::Request(package) => package.summary()?,
+            };
+            println!("session '{session_id}' asks to sign:\n{summary}");
+
+            let signed = session.sign(&request, &self.config.account, &self.share, &self.policy, &mut self.ledger);
+            self.auditor.record(session.audit_event(AuditEventKind::PartialSignature, Some(&request), AuditOutcome::of(&signed))?, &mut OsRng)?;
+            let envelope = signed?;
+            self.send(session_id, &envelope)?;
+            println!("session '{session_id}': partial signature sent");
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let config_path = match std::env::args().nth(1) {
+        Some(config_path) => config_path,
+        None => bail!("usage: signer <config file>"),
+    };
+    let config: SignerConfig = serde_json::from_str(&std::fs::read_to_string(config_path)?)?;
+    let password = match std::env::var("ALEO_FROST_PASSWORD") {
+        Ok(password) => password,
+        Err(..) => bail!("Set the keystore password in ALEO_FROST_PASSWORD"),
+    };
+
+    let SignerSecrets { share, identity_key } = Keystore::load(&config.keystore)?.decrypt(&password)?;
+    // The coordinator addresses signers by participant id, which only matches the share index in unweighted groups
+    ensure!(
+        share.participant_id == share.participant_index,
+        "Weighted shares are not supported by the signer daemon, share {} belongs to participant {}", share.participant_index, share.participant_id
+    );
+    ensure!(share.group_public_key == *config.account.group_public_key(), "The key share does not belong to the group's account");
+    let identity = Address::try_from(&identity_key)?;
+    ensure!(
+        config.group.participants.get(&share.participant_index) == Some(&identity),
+        "The identity key is not registered for participant {} in the group config", share.participant_index
+    );
+    std::fs::create_dir_all(&config.state_dir)?;
+
+    // The first entry of a new audit log records the key share the signer was dealt
+    let mut auditor = Auditor::open(config.state_dir.join("audit.jsonl"), identity_key)?;
+    if auditor.is_empty() {
+        let event = AuditEvent::keygen(&config.keystore.display().to_string(), &config.account.public_keys, AuditOutcome::Success)?;
+        auditor.record(event, &mut OsRng)?;
+    }
+
+    let mut signer = Signer {
+        identity_key,
+        share,
+        policy: SigningPolicy::load(&config.policy)?,
+        ledger: SpendingLedger::open(config.state_dir.join("spending.json"))?,
+        nonce_pool: NoncePool::open(config.state_dir.join("nonces.json"))?,
+        auditor,
+        sent: HashSet::new(),
+        config,
+    };
+    println!("signer for participant {} polling {}", signer.share.participant_index, signer.config.coordinator_url);
+
+    loop {
+        if let Err(error) = signer.poll() {
+            eprintln!("poll failed: {error}");
+        }
+        thread::sleep(Duration::from_secs(signer.config.poll_seconds));
+    }
+}
@@ -0,0 +1,611 @@
+use std::path::Path;
+
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::*,
+    api::SigningTarget,
+    audit::*,
+    envelope::*,
+    frost::*,
+    gamma::NonceShares,
+    identifier::Identifier,
+    keys::*,
+    policy::SigningPolicy,
+    preprocess::*,
+    request::RequestSigningPackage,
+    session::*,
+    spending::SpendingLedger,
+};
+
+/// A signing session exchanged as a file, for signers without a network connection
+///
+/// 1. The coordinator creates the bundle for a signing target and writes it out
+/// 2. Each signer imports it, adds its commitment with `commit` and exports it
+/// 3. The coordinator merges the bundles and calls `freeze` once t commitments are in
+/// 4. For requests, each signer adds its nonce shares and the coordinator merges them and
+///    calls `attach_nonce_shares`
+/// 5. Each signer adds its partial signature with `sign`
+/// 6. The coordinator merges the bundles and calls `aggregate` once every partial signature is in
+///
+/// Signers keep their nonce in a `SignerSession` file between steps 2 and 5, so a nonce is never
+/// used for two bundles or twice for the same bundle.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningBundle {
+    pub version: u16,
+    pub bundle_id: String,
+    pub target: SigningTarget,
+    // The number of signers needed
+    pub threshold: usize,
+    // The commitments of the signers that joined
+    pub commitments: Vec<SigningCommitment>,
+    // The package to sign, fixed from the first t commitments
+    pub request: Option<SigningRequest>,
+    // The signers' nonce shares, for requests
+    pub nonce_shares: Vec<NonceShares>,
+    pub partial_signatures: Vec<PartialThresholdSignature>,
+    pub result: Option<AggregationResult>,
+}
+
+impl SigningBundle {
+    pub fn new(bundle_id: &str, target: SigningTarget, threshold: usize) -> Result<Self> {
+        ensure!(threshold > 0, "A bundle needs at least one signer");
+
+        Ok(Self {
+            version: PROTOCOL_VERSION,
+            bundle_id: bundle_id.to_string(),
+            target,
+            threshold,
+            commitments: Vec::new(),
+            request: None,
+            nonce_shares: Vec::new(),
+            partial_signatures: Vec::new(),
+            result: None,
+        })
+    }
+
+    /// Read a bundle from a JSON file, e.g. on a USB stick
+    ///
+    /// The bundle's contents are checked for consistency, the proofs are checked with the
+    /// account when the bundle is merged.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bundle: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        bundle.validate()?;
+
+        Ok(bundle)
+    }
+
+    // Write the bundle to disk as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // The commitments of the package, once it is fixed
+    fn signing_commitments(&self) -> Option<&Vec<SigningCommitment>> {
+        self.request.as_ref().map(SigningRequest::signing_commitments)
+    }
+
+    // Add the signer's commitment from its session for this bundle
+    pub fn commit(&mut self, session: &mut SignerSession) -> Result<()> {
+        ensure!(session.session_id == self.bundle_id, "The session is for bundle '{}', not '{}'", session.session_id, self.bundle_id);
+        ensure!(self.request.is_none(), "Bundle '{}' no longer accepts commitments", self.bundle_id);
+
+        match session.announce()?.payload {
+            Payload::CommitmentAnnouncement(commitments) => self.add_commitments(&commitments),
+            payload => bail!("Expected a commitment announcement, found a {}", payload.name()),
+        }
+    }
+
+    // Keep one commitment per participant, refusing a second, different one and new ones once frozen
+    fn add_commitments(&mut self, commitments: &[SigningCommitment]) -> Result<()> {
+        for commitment in commitments {
+            match self.commitments.iter().find(|existing| existing.participant_index == commitment.participant_index) {
+                Some(existing) => ensure!(
+                    existing == commitment,
+                    "Participant {} has two different commitments in bundle '{}'", commitment.participant_index, self.bundle_id
+                ),
+                None => {
+                    ensure!(self.request.is_none(), "Bundle '{}' no longer accepts commitments", self.bundle_id);
+                    self.commitments.push(*commitment);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Check that the contents of the bundle are consistent with each other
+    fn validate(&self) -> Result<()> {
+        ensure!(self.version == PROTOCOL_VERSION, "Unsupported bundle version {}, expected {PROTOCOL_VERSION}", self.version);
+        ensure!(self.threshold > 0, "A bundle needs at least one signer");
+        for (index, commitment) in self.commitments.iter().enumerate() {
+            ensure!(
+                self.commitments[..index].iter().all(|existing| existing.participant_index != commitment.participant_index),
+                "Participant {} has two commitments in bundle '{}'", commitment.participant_index, self.bundle_id
+            );
+        }
+
+        let request = match &self.request {
+            Some(request) => request,
+            None => {
+                ensure!(
+                    self.nonce_shares.is_empty() && self.partial_signatures.is_empty() && self.result.is_none(),
+                    "Bundle '{}' has contributions but no package", self.bundle_id
+                );
+                return Ok(());
+            }
+        };
+        // The package must be the one freezing the bundle's commitments gives
+        ensure!(
+            without_nonce_shares(request) == self.frozen_request()?,
+            "The package of bundle '{}' does not match its target and commitments", self.bundle_id
+        );
+
+        let signers = self.signers();
+        match request {
+            SigningRequest::Request(package) => {
+                // The attached nonce shares must be the ones collected in the bundle
+                for nonce_shares in &package.nonce_shares {
+                    ensure!(
+                        self.nonce_shares.contains(nonce_shares),
+                        "The package of bundle '{}' holds other nonce shares from participant {}", self.bundle_id, nonce_shares.participant_index
+                    );
+                }
+            }
+            SigningRequest::Message(..) => {
+                ensure!(self.nonce_shares.is_empty(), "Bundle '{}' signs a message but holds nonce shares", self.bundle_id)
+            }
+        }
+        for (index, nonce_shares) in self.nonce_shares.iter().enumerate() {
+            ensure!(
+                signers.contains(&nonce_shares.participant_index)
+                    && self.nonce_shares[..index].iter().all(|existing| existing.participant_index != nonce_shares.participant_index),
+                "Unexpected nonce shares from participant {} in bundle '{}'", nonce_shares.participant_index, self.bundle_id
+            );
+        }
+        for (index, partial_signature) in self.partial_signatures.iter().enumerate() {
+            ensure!(
+                signers.contains(&partial_signature.participant_index)
+                    && self.partial_signatures[..index].iter().all(|existing| existing.participant_index != partial_signature.participant_index),
+                "Unexpected partial signature from participant {} in bundle '{}'", partial_signature.participant_index, self.bundle_id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Merge the contributions of another copy of the bundle
+    ///
+    /// The package of the other copy must be the one this copy freezes to, every set of nonce
+    /// shares is checked against its signer's public key and commitment, and a result is only
+    /// kept if it is the aggregate of the partial signatures. Copies that disagree on the target,
+    /// on the package or on any participant's contribution are rejected.
+    pub fn merge(&mut self, other: &SigningBundle, account: &ThresholdAccount) -> Result<()> {
+        ensure!(
+            other.bundle_id == self.bundle_id && other.target == self.target && other.threshold == self.threshold,
+            "Cannot merge bundle '{}' into bundle '{}'", other.bundle_id, self.bundle_id
+        );
+        other.validate()?;
+
+        self.add_commitments(&other.commitments)?;
+        if let Some(other_request) = &other.request {
+            if self.request.is_none() {
+                self.freeze()?;
+            }
+            ensure!(
+                self.request.as_ref().map(without_nonce_shares) == Some(without_nonce_shares(other_request)),
+                "The copies of bundle '{}' sign different packages", self.bundle_id
+            );
+        }
+
+        for nonce_shares in &other.nonce_shares {
+            self.add_nonce_share_set(nonce_shares, account)?;
+        }
+        // Attach the nonce shares here too once the other copy has attached them
+        let attached = matches!(&self.request, Some(SigningRequest::Request(package)) if package.has_nonce_shares());
+        if !attached && matches!(&other.request, Some(SigningRequest::Request(package)) if package.has_nonce_shares()) {
+            self.attach_nonce_shares()?;
+        }
+
+        for partial_signature in &other.partial_signatures {
+            match self.partial_signatures.iter().find(|existing| existing.participant_index == partial_signature.participant_index) {
+                Some(existing) => ensure!(
+                    existing == partial_signature,
+                    "Participant {} has two different partial signatures in bundle '{}'", partial_signature.participant_index, self.bundle_id
+                ),
+                None => self.partial_signatures.push(*partial_signature),
+            }
+        }
+
+        if let Some(result) = &other.result {
+            let expected = self.aggregated(account)?;
+            ensure!(*result == expected, "The result of bundle '{}' does not match its partial signatures", self.bundle_id);
+            self.result = Some(expected);
+        }
+
+        Ok(())
+    }
+
+    // The package signing the target with the first t commitments, ordered by identifier
+    fn frozen_request(&self) -> Result<SigningRequest> {
+        ensure!(
+            self.commitments.len() >= self.threshold,
+            "Bundle '{}' has {} of {} commitments", self.bundle_id, self.commitments.len(), self.threshold
+        );
+
+        let mut commitments = self.commitments.clone();
+        commitments.sort_by_key(|commitment| commitment.participant_index);
+        commitments.truncate(self.threshold);
+
+        Ok(match self.target.clone() {
+            SigningTarget::Message(message) => SigningRequest::Message(SigningPackage::new(commitments, message)),
+            SigningTarget::Call { program_id, function_name, inputs, input_types } => {
+                SigningRequest::Request(RequestSigningPackage::new(program_id, function_name, inputs, input_types, commitments)?)
+            }
+        })
+    }
+
+    /// Fix the package to sign from the first t commitments, ordered by identifier
+    pub fn freeze(&mut self) -> Result<()> {
+        ensure!(self.request.is_none(), "Bundle '{}' is already frozen", self.bundle_id);
+
+        self.request = Some(self.frozen_request()?);
+        Ok(())
+    }
+
+    // The signers of the frozen package
+    pub fn signers(&self) -> Vec<Identifier> {
+        self.signing_commitments().map_or_else(Vec::new, |commitments| commitments.iter().map(|commitment| commitment.participant_index).collect())
+    }
+
+    // Keep one verified set of nonce shares per signer, refusing a second, different set
+    fn add_nonce_share_set(&mut self, nonce_shares: &NonceShares, account: &ThresholdAccount) -> Result<()> {
+        let participant_index = nonce_shares.participant_index;
+        let signing_commitment = match &self.request {
+            Some(SigningRequest::Request(package)) => {
+                match package.signing_commitments.iter().find(|commitment| commitment.participant_index == participant_index) {
+                    Some(signing_commitment) => *signing_commitment,
+                    None => bail!("Participant {participant_index} does not sign bundle '{}'", self.bundle_id),
+                }
+            }
+            _ => bail!("Bundle '{}' has no request", self.bundle_id),
+        };
+        let public_key = match account.public_keys.public_keys.get(&participant_index) {
+            Some(public_key) => public_key,
+            None => bail!("Participant {participant_index} is not part of the group"),
+        };
+        nonce_shares.verify(&account.address()?, public_key, &signing_commitment)?;
+
+        match self.nonce_shares.iter().find(|existing| existing.participant_index == nonce_shares.participant_index) {
+            Some(existing) => ensure!(
+                existing == nonce_shares,
+                "Participant {} has two different sets of nonce shares in bundle '{}'", nonce_shares.participant_index, self.bundle_id
+            ),
+            None => self.nonce_shares.push(nonce_shares.clone()),
+        }
+
+        Ok(())
+    }
+
+    // Add the signer's nonce shares for the frozen request
+    pub fn add_nonce_shares<R: Rng>(
+        &mut self,
+        session: &mut SignerSession,
+        account: &ThresholdAccount,
+        share: &SignerShare,
+        rng: &mut R,
+    ) -> Result<()> {
+        let package = match &self.request {
+            Some(SigningRequest::Request(package)) => package.clone(),
+            _ => bail!("Bundle '{}' has no request", self.bundle_id),
+        };
+
+        match session.nonce_shares(&package, account, share, rng)?.payload {
+            Payload::NonceShares(nonce_shares) => self.add_nonce_share_set(&nonce_shares, account),
+            payload => bail!("Expected nonce shares, found a {}", payload.name()),
+        }
+    }
+
+    // Attach the nonce shares to the request once every signer's shares are in
+    pub fn attach_nonce_shares(&mut self) -> Result<()> {
+        let signers = self.signers();
+        let package = match &mut self.request {
+            Some(SigningRequest::Request(package)) => package,
+            _ => bail!("Bundle '{}' has no request", self.bundle_id),
+        };
+        for signer in &signers {
+            ensure!(
+                self.nonce_shares.iter().any(|nonce_shares| nonce_shares.participant_index == *signer),
+                "Missing nonce shares from participant {signer}"
+            );
+        }
+
+        package.nonce_shares = self.nonce_shares.iter().filter(|nonce_shares| signers.contains(&nonce_shares.participant_index)).cloned().collect();
+        Ok(())
+    }
+
+    /// Add the signer's partial signature on the frozen package, if its policy allows it
+    ///
+    /// The signer sees the package's summary through the policy's clear-signing check.
+    pub fn sign(
+        &mut self,
+        session: &mut SignerSession,
+        account: &ThresholdAccount,
+        share: &SignerShare,
+        policy: &SigningPolicy,
+        ledger: &mut SpendingLedger,
+    ) -> Result<()> {
+        let request = match &self.request {
+            Some(request) => request.clone(),
+            None => bail!("Bundle '{}' is not frozen yet", self.bundle_id),
+        };
+        if let SigningRequest::Request(package) = &request {
+            ensure!(package.has_nonce_shares(), "The nonce shares must be attached to bundle '{}' before signing", self.bundle_id);
+        }
+
+        match session.sign(&request, account, share, policy, ledger)?.payload {
+            Payload::PartialSignature(partial_signature) => {
+                self.partial_signatures.retain(|existing| existing.participant_index != partial_signature.participant_index);
+                self.partial_signatures.push(partial_signature);
+                Ok(())
+            }
+            payload => bail!("Expected a partial signature, found a {}", payload.name()),
+        }
+    }
+
+    // Aggregate the signature once every signer of the package has signed
+    pub fn aggregate(&mut self, account: &ThresholdAccount) -> Result<AggregationResult> {
+        let result = self.aggregated(account)?;
+        self.result = Some(result.clone());
+
+        Ok(result)
+    }
+
+    // The aggregate of the signers' partial signatures, each checked against its signer's public key share
+    fn aggregated(&self, account: &ThresholdAccount) -> Result<AggregationResult> {
+        let signers = self.signers();
+        let mut partial_signatures = Vec::with_capacity(signers.len());
+        for signer in &signers {
+            match self.partial_signatures.iter().find(|partial_signature| partial_signature.participant_index == *signer) {
+                Some(partial_signature) => partial_signatures.push(*partial_signature),
+                None => bail!("Missing the partial signature of participant {signer}"),
+            }
+        }
+
+        Ok(match &self.request {
+            Some(SigningRequest::Message(package)) => AggregationResult::Signature(package.aggregate(account, &partial_signatures)?),
+            Some(SigningRequest::Request(package)) => AggregationResult::Request(Box::new(package.aggregate(account, &partial_signatures)?)),
+            None => bail!("Bundle '{}' is not frozen yet", self.bundle_id),
+        })
+    }
+
+    /// The audit event of a step of this bundle, for the audit log of the party taking it
+    ///
+    /// Before the bundle is frozen the event lists every commitment that joined.
+    pub fn audit_event(&self, kind: AuditEventKind, participants: Vec<Identifier>, outcome: AuditOutcome) -> Result<AuditEvent> {
+        Ok(AuditEvent {
+            kind,
+            session_id: self.bundle_id.clone(),
+            message_hash: self.request.as_ref().map(|request| request.message_hash()).transpose()?,
+            participants,
+            commitments: self.signing_commitments().unwrap_or(&self.commitments).clone(),
+            outcome,
+        })
+    }
+}
+
+// The request without the nonce shares attached to it
+fn without_nonce_shares(request: &SigningRequest) -> SigningRequest {
+    match request {
+        SigningRequest::Request(package) => SigningRequest::Request(RequestSigningPackage { nonce_shares: Vec::new(), ..package.clone() }),
+        request => request.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::generate_message::SigningMessage;
+
+    use snarkvm_console_account::{Address, PrivateKey};
+    use snarkvm_console_network::TestnetV0;
+    use snarkvm_console_program::{Identifier as FunctionName, ProgramID, Value, ValueType};
+    use snarkvm_console_types::Scalar;
+    use snarkvm_console_types_scalar::{TestRng, Zero};
+    use std::{path::PathBuf, str::FromStr};
+
+    fn temp_path(name: &str, rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("aleo-frost-bundle-{name}-{}.json", rng.gen::<u64>()))
+    }
+
+    #[test]
+    fn test_merge_checks_the_other_copy() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+
+        let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+        let mut bundle = SigningBundle::new("bundle-1", SigningTarget::Message(message), 2).unwrap();
+
+        // Every signer commits on its own copy
+        let mut sessions = Vec::with_capacity(shares.len());
+        let mut copies = Vec::with_capacity(shares.len());
+        for share in &shares {
+            let mut session = SignerSession::create(temp_path("session", rng), "bundle-1", share.participant_index, 600, rng).unwrap();
+            let mut copy = bundle.clone();
+            copy.commit(&mut session).unwrap();
+            sessions.push(session);
+            copies.push(copy);
+        }
+
+        // A copy frozen on other commitments than this copy freezes to is refused
+        let mut early = bundle.clone();
+        early.merge(&copies[1], &account).unwrap();
+        early.merge(&copies[2], &account).unwrap();
+        early.freeze().unwrap();
+        for copy in &copies {
+            bundle.merge(copy, &account).unwrap();
+        }
+        assert!(bundle.clone().merge(&early, &account).is_err());
+
+        // Each signer of the frozen package signs its own copy
+        bundle.freeze().unwrap();
+        let signers = bundle.signers();
+        for (share, session) in shares.iter().zip(&mut sessions) {
+            if !signers.contains(&share.participant_index) {
+                continue;
+            }
+            let mut copy = bundle.clone();
+            let mut ledger = SpendingLedger::open(temp_path("spending", rng)).unwrap();
+            copy.sign(session, &account, share, &SigningPolicy::default(), &mut ledger).unwrap();
+            bundle.merge(&copy, &account).unwrap();
+        }
+        let result = bundle.clone().aggregate(&account).unwrap();
+
+        // A result other than the aggregate of the partial signatures is refused
+        let mut forged = bundle.clone();
+        forged.result = Some(AggregationResult::Failure("forged".to_string()));
+        assert!(bundle.clone().merge(&forged, &account).is_err());
+
+        let mut signed = bundle.clone();
+        signed.result = Some(result.clone());
+        bundle.merge(&signed, &account).unwrap();
+        assert_eq!(bundle.result, Some(result));
+    }
+
+    // A 2-of-3 account and a bundle for the signing target with a session for every signer
+    fn setup(target: SigningTarget, rng: &mut TestRng) -> (ThresholdAccount, Vec<SignerShare>, Vec<SignerSession>, SigningBundle) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+
+        let bundle = SigningBundle::new("bundle-1", target, 2).unwrap();
+        let sessions = shares
+            .iter()
+            .map(|share| SignerSession::create(temp_path("session", rng), "bundle-1", share.participant_index, 600, rng).unwrap())
+            .collect();
+
+        (account, shares, sessions, bundle)
+    }
+
+    // Pass a copy of the bundle through a file, as between an offline signer and the coordinator
+    fn carry(bundle: &SigningBundle, rng: &mut TestRng) -> SigningBundle {
+        let path = temp_path("file", rng);
+        bundle.save(&path).unwrap();
+        let loaded = SigningBundle::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn test_request_flow_produces_a_verifying_request() {
+        let rng = &mut TestRng::default();
+        let recipient = Address::try_from(&PrivateKey::<TestnetV0>::new(rng).unwrap()).unwrap();
+        let target = SigningTarget::Call {
+            program_id: ProgramID::from_str("credits.aleo").unwrap(),
+            function_name: FunctionName::from_str("transfer_public").unwrap(),
+            inputs: vec![Value::from_str(&recipient.to_string()).unwrap(), Value::from_str("10u64").unwrap()],
+            input_types: vec![ValueType::from_str("address.public").unwrap(), ValueType::from_str("u64.public").unwrap()],
+        };
+        let (account, shares, mut sessions, mut bundle) = setup(target, rng);
+        let signers = [0, 1];
+
+        // Every signer commits on its own copy, and the coordinator freezes the merged bundle
+        for &signer in &signers {
+            let mut copy = carry(&bundle, rng);
+            copy.commit(&mut sessions[signer]).unwrap();
+            bundle.merge(&carry(&copy, rng), &account).unwrap();
+        }
+        bundle.freeze().unwrap();
+
+        // Every signer adds its nonce shares on its own copy, and the coordinator attaches them
+        for &signer in &signers {
+            let mut copy = carry(&bundle, rng);
+            copy.add_nonce_shares(&mut sessions[signer], &account, &shares[signer], rng).unwrap();
+            bundle.merge(&carry(&copy, rng), &account).unwrap();
+        }
+        bundle.attach_nonce_shares().unwrap();
+
+        // Every signer signs its own copy
+        for &signer in &signers {
+            let mut copy = carry(&bundle, rng);
+            let mut ledger = SpendingLedger::open(temp_path("spending", rng)).unwrap();
+            copy.sign(&mut sessions[signer], &account, &shares[signer], &SigningPolicy::default(), &mut ledger).unwrap();
+            bundle.merge(&carry(&copy, rng), &account).unwrap();
+        }
+
+        let input_types = match &bundle.request {
+            Some(SigningRequest::Request(package)) => package.input_types.clone(),
+            request => panic!("Expected a request package, found {request:?}"),
+        };
+        match bundle.aggregate(&account).unwrap() {
+            AggregationResult::Request(request) => {
+                assert_eq!(*request.signer(), account.address().unwrap());
+                assert!(request.verify(&input_types));
+            }
+            result => panic!("Expected a request, found {result:?}"),
+        }
+        assert_eq!(carry(&bundle, rng), bundle);
+    }
+
+    #[test]
+    fn test_tampered_file_is_refused() {
+        let rng = &mut TestRng::default();
+        let target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 10u64 }").unwrap());
+        let (_, shares, mut sessions, mut bundle) = setup(target, rng);
+        for session in &mut sessions {
+            bundle.commit(session).unwrap();
+        }
+        bundle.freeze().unwrap();
+        assert_eq!(carry(&bundle, rng), bundle);
+
+        let load = |tampered: &SigningBundle, rng: &mut TestRng| {
+            let path = temp_path("file", rng);
+            std::fs::write(&path, serde_json::to_string(tampered).unwrap()).unwrap();
+            let loaded = SigningBundle::load(&path);
+            std::fs::remove_file(&path).unwrap();
+            loaded
+        };
+
+        // Another protocol version
+        let mut tampered = bundle.clone();
+        tampered.version += 1;
+        assert!(load(&tampered, rng).is_err());
+
+        // A package for another target than the bundle's
+        let mut tampered = bundle.clone();
+        tampered.target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 1000u64 }").unwrap());
+        assert!(load(&tampered, rng).is_err());
+
+        // Commitments that no longer give the frozen package
+        let mut tampered = bundle.clone();
+        tampered.commitments.retain(|commitment| commitment.participant_index != shares[0].participant_index);
+        assert!(load(&tampered, rng).is_err());
+
+        // A second commitment from the same participant
+        let mut tampered = bundle.clone();
+        let (_, commitments) = preprocess(1, shares[2].participant_index, rng);
+        tampered.commitments.push(commitments[0]);
+        assert!(load(&tampered, rng).is_err());
+
+        // A partial signature from a participant that is not a signer of the package
+        let mut tampered = bundle.clone();
+        tampered.partial_signatures.push(PartialThresholdSignature { participant_index: shares[2].participant_index, partial_signature: Scalar::<TestnetV0>::zero() });
+        assert!(load(&tampered, rng).is_err());
+
+        // Unknown fields
+        let path = temp_path("file", rng);
+        let mut json = serde_json::to_value(&bundle).unwrap();
+        json["signers"] = serde_json::json!([]);
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(SigningBundle::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
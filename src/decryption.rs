@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use snarkvm_console_account::compute_key::*;
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_program::{Ciphertext, Plaintext, Record};
+use snarkvm_console_types::{Field, Group, Scalar};
+use snarkvm_console_types_scalar::{bail, ensure, Result, Zero};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{dleq::*, identifier::Identifier, keys::*, utils::*};
+
+/// The part of the view key of a threshold account that is not threshold-shared
+///
+/// view_key = sk_sig + (r_sig + sk_prf), where sk_sig is threshold-shared. The terms hold the
+/// sum r_sig + sk_prf, derived by the dealer at setup. sk_prf = H(pk_sig, pr_sig) is computed
+/// from public values, so the offset reveals r_sig and must be kept as secret as r_sig itself.
+///
+/// The terms are held by whoever combines decryption shares, never in signer or coordinator
+/// state. Neither holds a view key (see `ThresholdAccount`), so the terms alone do not give sk_sig,
+/// but anyone holding them together with a record's decryption shares can read the record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViewKeyTerms {
+    // The account's r_sig + sk_prf, with sk_prf = H(pk_sig, pr_sig)
+    pub offset: Scalar<TestnetV0>,
+}
+
+impl ViewKeyTerms {
+    // Derive the view key terms of the group from r_sig, checking it against pr_sig
+    pub fn new(
+        group_public_key: &GroupPublicKey,
+        pr_sig: Group<TestnetV0>,
+        r_sig: Scalar<TestnetV0>,
+    ) -> Result<Self> {
+        ensure!(Network::g_scalar_multiply(&r_sig) == pr_sig, "r_sig does not match the account's pr_sig");
+        let compute_key = ComputeKey::<TestnetV0>::try_from((group_public_key.0, pr_sig))?;
+
+        Ok(Self { offset: r_sig + compute_key.sk_prf() })
+    }
+
+    // Read the view key terms from a JSON file on disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    // Write the view key terms to disk, readable by the owner only
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_private(path.as_ref(), serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+/// A participant's partial ECDH point for decrypting a record owned by the threshold account
+///
+/// partial_i = nonce^s_i with a DLEQ proof against the participant's public key G^s_i
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptionShare {
+    // The identifier of the participant
+    pub participant_index: Identifier,
+    // The nonce of the record being decrypted
+    pub nonce: Group<TestnetV0>,
+    // The participant's partial ECDH point nonce^s_i
+    pub partial: Group<TestnetV0>,
+    // The proof that log_nonce(partial_i) == log_G(Y_i)
+    pub proof: DleqProof,
+}
+
+impl DecryptionShare {
+    // Generate the participant's decryption share for a record ciphertext
+    //
+    // Signers go through `SigningPolicy::decryption_share`, which checks the policy allows it
+    pub(crate) fn new<R: Rng>(
+        participant_signing_share: &SignerShare,
+        record: &Record<TestnetV0, Ciphertext<TestnetV0>>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let nonce = *record.nonce();
+
+        Ok(Self {
+            participant_index: participant_signing_share.participant_index,
+            nonce,
+            partial: nonce * participant_signing_share.secret_key.0,
+            proof: DleqProof::prove(&participant_signing_share.secret_key.0, nonce, rng)?,
+        })
+    }
+
+    // Verify the DLEQ proof against the participant's public key
+    pub fn verify(&self, public_key: &SignerPublicKey) -> bool {
+        self.proof.verify(self.nonce, public_key.0, self.partial)
+    }
+}
+
+/// Derive the record view key from the signers' decryption shares
+///
+/// record_view_key = (nonce^view_key).x where
+/// nonce^view_key = Sum lambda_i * partial_i + nonce^offset = nonce^sk_sig * nonce^(r_sig + sk_prf)
+///
+/// Every decryption share is checked against the participant's public key first.
+pub fn combine_decryption_shares(
+    record: &Record<TestnetV0, Ciphertext<TestnetV0>>,
+    decryption_shares: &[DecryptionShare],
+    public_keys: &PublicKeys,
+    view_key_terms: &ViewKeyTerms,
+) -> Result<Field<TestnetV0>> {
+    let nonce = *record.nonce();
+    let participant_indexes: Vec<Identifier> = decryption_shares.iter().map(|share| share.participant_index).collect();
+
+    let mut result = Group::<TestnetV0>::zero();
+    for share in decryption_shares {
+        ensure!(share.nonce == nonce, "Decryption share from participant {} is for a different record", share.participant_index);
+        match public_keys.public_keys.get(&share.participant_index) {
+            Some(public_key) => ensure!(share.verify(public_key), "Invalid decryption share proof from participant {}", share.participant_index),
+            None => bail!("Participant {} is not part of the group", share.participant_index),
+        }
+
+        let lambda_i = calculate_lagrange_coefficients(share.participant_index, &participant_indexes, None)?;
+        result = result + share.partial * lambda_i;
+    }
+
+    result = result + nonce * view_key_terms.offset;
+
+    Ok(result.to_x_coordinate())
+}
+
+/// Decrypt a record owned by the threshold account from the signers' decryption shares
+///
+/// Decryption fails if the shares do not reach the threshold, as the decrypted owner then
+/// does not match the account's address.
+pub fn threshold_decrypt(
+    record: &Record<TestnetV0, Ciphertext<TestnetV0>>,
+    decryption_shares: &[DecryptionShare],
+    public_keys: &PublicKeys,
+    view_key_terms: &ViewKeyTerms,
+    pr_sig: Group<TestnetV0>,
+) -> Result<Record<TestnetV0, Plaintext<TestnetV0>>> {
+    let record_view_key = combine_decryption_shares(record, decryption_shares, public_keys, view_key_terms)?;
+    let plaintext = record.decrypt_symmetric_unchecked(&record_view_key)?;
+
+    let address = ComputeKey::<TestnetV0>::try_from((public_keys.group_public_key.0, pr_sig))?.to_address();
+    ensure!(**plaintext.owner() == address, "Decrypted record is not owned by the threshold account");
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::{TestRng, Uniform};
+    use std::str::FromStr;
+
+    use crate::{account::ThresholdAccount, policy::SigningPolicy};
+
+    // A 2-of-3 account, its view key terms and a record it owns
+    fn setup(
+        rng: &mut TestRng,
+    ) -> (Vec<SignerShare>, ThresholdAccount, ViewKeyTerms, Record<TestnetV0, Plaintext<TestnetV0>>, Record<TestnetV0, Ciphertext<TestnetV0>>) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+        let view_key_terms = ViewKeyTerms::new(account.group_public_key(), account.pr_sig, private_key.r_sig()).unwrap();
+
+        let randomizer = Scalar::<TestnetV0>::rand(rng);
+        let nonce = TestnetV0::g_scalar_multiply(&randomizer);
+        let record = Record::<TestnetV0, Plaintext<TestnetV0>>::from_str(&format!(
+            "{{ owner: {}.private, microcredits: 100u64.private, _nonce: {nonce}.public }}",
+            account.address().unwrap()
+        ))
+        .unwrap();
+        let ciphertext = record.encrypt(randomizer).unwrap();
+
+        (shares, account, view_key_terms, record, ciphertext)
+    }
+
+    #[test]
+    fn test_threshold_decrypt() {
+        let rng = &mut TestRng::default();
+        let (shares, account, view_key_terms, record, ciphertext) = setup(rng);
+        let policy = SigningPolicy { allow_decryption: true, ..Default::default() };
+
+        let decryption_shares: Vec<DecryptionShare> =
+            shares[1..].iter().map(|share| policy.decryption_share(&ciphertext, share, rng).unwrap()).collect();
+        let decrypted = threshold_decrypt(&ciphertext, &decryption_shares, &account.public_keys, &view_key_terms, account.pr_sig).unwrap();
+        assert_eq!(decrypted, record);
+
+        // A single share is below the threshold
+        assert!(threshold_decrypt(&ciphertext, &decryption_shares[..1], &account.public_keys, &view_key_terms, account.pr_sig).is_err());
+    }
+
+    #[test]
+    fn test_bad_share_is_rejected() {
+        let rng = &mut TestRng::default();
+        let (shares, account, view_key_terms, _, ciphertext) = setup(rng);
+        let policy = SigningPolicy { allow_decryption: true, ..Default::default() };
+        let decryption_shares: Vec<DecryptionShare> =
+            shares[..2].iter().map(|share| policy.decryption_share(&ciphertext, share, rng).unwrap()).collect();
+
+        // A partial point that does not match its proof
+        let mut tampered = decryption_shares.clone();
+        tampered[0].partial = tampered[0].partial + Group::<TestnetV0>::generator();
+        assert!(combine_decryption_shares(&ciphertext, &tampered, &account.public_keys, &view_key_terms).is_err());
+
+        // A share passed off as another participant's
+        let mut tampered = decryption_shares.clone();
+        tampered[0].participant_index = shares[2].participant_index;
+        assert!(combine_decryption_shares(&ciphertext, &tampered, &account.public_keys, &view_key_terms).is_err());
+
+        // A share for a different record
+        let (_, _, _, _, other) = setup(rng);
+        let other_share = policy.decryption_share(&other, &shares[1], rng).unwrap();
+        let mixed = vec![decryption_shares[0], other_share];
+        assert!(combine_decryption_shares(&ciphertext, &mixed, &account.public_keys, &view_key_terms).is_err());
+    }
+
+    #[test]
+    fn test_policy_refuses_decryption() {
+        let rng = &mut TestRng::default();
+        let (shares, _, _, _, ciphertext) = setup(rng);
+
+        assert!(SigningPolicy::default().decryption_share(&ciphertext, &shares[0], rng).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types::{Field, Group};
+use snarkvm_console_types_scalar::{ensure, Result};
+use snarkvm_ledger_block::{Deployment, ProgramOwner};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{account::*, frost::*, generate_message::SigningMessage, keys::*, preprocess::*, summary::Summarize};
+
+/// A deployment to be signed by the threshold account as its program owner, together with the
+/// signers' commitments
+///
+/// The `ProgramOwner` signature is an Aleo signature over the message [deployment_id]. Signers
+/// are given the deployment itself rather than its id, so the summary they approve shows the
+/// program and its verifying keys and the id is recomputed from them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentSigningPackage {
+    // Each participant's public signing commitment
+    pub signing_commitments: Vec<SigningCommitment>,
+    // The deployment the account signs as owner
+    pub deployment: Deployment<TestnetV0>,
+}
+
+impl DeploymentSigningPackage {
+    pub fn new(
+        deployment: Deployment<TestnetV0>,
+        signing_commitments: Vec<SigningCommitment>,
+    ) -> Self {
+        Self { signing_commitments, deployment }
+    }
+
+    // The id of the deployment, the message signed by the program owner
+    pub fn deployment_id(&self) -> Result<Field<TestnetV0>> {
+        self.deployment.to_deployment_id()
+    }
+
+    // The package signing the deployment id as a message
+    fn signing_package(&self) -> Result<SigningPackage> {
+        Ok(SigningPackage::new(self.signing_commitments.clone(), SigningMessage::from_fields(vec![self.deployment_id()?])))
+    }
+
+    // Generate a participant's partial signature, refusing deployments without a clear-signing summary
+    pub fn partial_sign(
+        &self,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        self.summary()?;
+
+        PartialThresholdSignature::new_partial_sig(
+            participant_signing_share,
+            signing_nonce,
+            self.signing_commitments.clone(),
+            vec![self.deployment_id()?],
+            pr_sig,
+        )
+    }
+
+    /// Aggregate the signers' partial signatures into the `ProgramOwner` of the deployment
+    ///
+    /// This lets the threshold account deploy programs without ever reconstructing its private key.
+    /// The program owner is checked with `ProgramOwner::verify` before it is returned.
+    pub fn aggregate(
+        &self,
+        account: &ThresholdAccount,
+        partial_signatures: &[PartialThresholdSignature],
+    ) -> Result<ProgramOwner<TestnetV0>> {
+        let deployment_id = self.deployment_id()?;
+        let signature = self.signing_package()?.aggregate(account, partial_signatures)?;
+
+        let owner = ProgramOwner::from(account.address()?, signature);
+        ensure!(owner.verify(deployment_id), "The threshold signed program owner failed to verify");
+
+        Ok(owner)
+    }
+}
+
+/// Build the signing package for the owner of a deployment
+pub fn program_owner_signing_package(
+    deployment: Deployment<TestnetV0>,
+    signing_commitments: Vec<SigningCommitment>,
+) -> DeploymentSigningPackage {
+    DeploymentSigningPackage::new(deployment, signing_commitments)
+}
+
+// Aggregate the signers' partial signatures into the `ProgramOwner` of a deployment
+pub fn threshold_program_owner(
+    deployment: Deployment<TestnetV0>,
+    account: &ThresholdAccount,
+    signing_commitments: Vec<SigningCommitment>,
+    partial_signatures: &[PartialThresholdSignature],
+) -> Result<ProgramOwner<TestnetV0>> {
+    program_owner_signing_package(deployment, signing_commitments).aggregate(account, partial_signatures)
+}
@@ -0,0 +1,108 @@
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Field, Group, Scalar};
+use snarkvm_console_types_scalar::{One, Result, Uniform};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator for the DLEQ proof challenge
+const DLEQ_DOMAIN: &str = "AleoFrostDLEQ";
+
+/// A Chaum-Pedersen proof that two points share the same discrete logarithm
+///
+/// Proves knowledge of s such that X = G^s and Y = H^s without revealing s:
+/// - k = random nonce, (A_1, A_2) = (G^k, H^k)
+/// - c = H(domain, G, H, X, Y, A_1, A_2)
+/// - z = k - c * s
+///
+/// The verifier recomputes A_1 = G^z * X^c and A_2 = H^z * Y^c and checks the challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DleqProof {
+    // The challenge c
+    pub challenge: Scalar<TestnetV0>,
+    // The response z
+    pub response: Scalar<TestnetV0>,
+}
+
+impl DleqProof {
+    // Prove that log_G(G^secret) == log_H(H^secret)
+    pub fn prove<R: Rng>(
+        secret: &Scalar<TestnetV0>,
+        h: Group<TestnetV0>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let x = Network::g_scalar_multiply(secret);
+        let y = h * secret;
+
+        let nonce = Scalar::<TestnetV0>::rand(rng);
+        let challenge = Self::challenge(h, x, y, Network::g_scalar_multiply(&nonce), h * nonce)?;
+
+        Ok(Self { challenge, response: nonce - challenge * secret })
+    }
+
+    // Verify that log_G(x) == log_H(y)
+    pub fn verify(
+        &self,
+        h: Group<TestnetV0>,
+        x: Group<TestnetV0>,
+        y: Group<TestnetV0>,
+    ) -> bool {
+        let a_1 = Network::g_scalar_multiply(&self.response) + x * self.challenge;
+        let a_2 = h * self.response + y * self.challenge;
+
+        match Self::challenge(h, x, y, a_1, a_2) {
+            Ok(challenge) => challenge == self.challenge,
+            Err(..) => false,
+        }
+    }
+
+    fn challenge(
+        h: Group<TestnetV0>,
+        x: Group<TestnetV0>,
+        y: Group<TestnetV0>,
+        a_1: Group<TestnetV0>,
+        a_2: Group<TestnetV0>,
+    ) -> Result<Scalar<TestnetV0>> {
+        let generator = Network::g_scalar_multiply(&Scalar::<TestnetV0>::one());
+
+        let mut preimage = vec![Field::<TestnetV0>::new_domain_separator(DLEQ_DOMAIN)];
+        preimage.extend([generator, h, x, y, a_1, a_2].map(|point| point.to_x_coordinate()));
+
+        TestnetV0::hash_to_scalar_psd8(&preimage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_dleq_proof() {
+        let rng = &mut TestRng::default();
+        let secret = Scalar::<TestnetV0>::rand(rng);
+        let h = Network::g_scalar_multiply(&Scalar::<TestnetV0>::rand(rng));
+        let (x, y) = (Network::g_scalar_multiply(&secret), h * secret);
+
+        let proof = DleqProof::prove(&secret, h, rng).unwrap();
+        assert!(proof.verify(h, x, y));
+
+        // Y with a different discrete logarithm than X
+        let other = Scalar::<TestnetV0>::rand(rng);
+        assert!(!proof.verify(h, x, h * other));
+        assert!(!DleqProof::prove(&other, h, rng).unwrap().verify(h, x, y));
+
+        // The proof is bound to its base and points
+        let other_h = Network::g_scalar_multiply(&Scalar::<TestnetV0>::rand(rng));
+        assert!(!proof.verify(other_h, x, other_h * secret));
+        assert!(!proof.verify(h, y, x));
+
+        // Forged challenges and responses
+        let one = Scalar::<TestnetV0>::one();
+        assert!(!DleqProof { challenge: proof.challenge + one, ..proof }.verify(h, x, y));
+        assert!(!DleqProof { response: proof.response + one, ..proof }.verify(h, x, y));
+        assert!(!DleqProof { challenge: proof.response, response: proof.challenge }.verify(h, x, y));
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use snarkvm_console_account::signature::*;
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::Request;
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{audit::message_hash, frost::*, gamma::NonceShares, identifier::Identifier, identity::SignedEnvelope, keys::*, preprocess::*, request::RequestSigningPackage};
+
+/// The version of the wire protocol, envelopes of any other version are rejected
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// A party of a signing session
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum Party {
+    // The dealer or coordinator running the session
+    Coordinator,
+    // A participant, by the identifier of its key share
+    Participant(Identifier),
+    // Every party of the session, only valid as a recipient
+    Broadcast,
+}
+
+/// A key share dealt to a participant, sent over a confidential channel only
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeygenPackage {
+    pub share: SignerShare,
+    pub public_key_package: PublicKeyPackage,
+}
+
+/// What the coordinator asks the participants to sign
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum SigningRequest {
+    Message(SigningPackage),
+    Request(RequestSigningPackage),
+}
+
+impl SigningRequest {
+    // The signers' commitments of the package
+    pub fn signing_commitments(&self) -> &Vec<SigningCommitment> {
+        match self {
+            Self::Message(package) => &package.signing_commitments,
+            Self::Request(package) => &package.signing_commitments,
+        }
+    }
+
+    // Hash the package for the audit log: the signed message, or the call the request binds to
+    pub fn message_hash(&self) -> Result<Field<TestnetV0>> {
+        match self {
+            Self::Message(package) => message_hash(&package.message.to_fields()?),
+            Self::Request(package) => message_hash(&package.binding_message()?),
+        }
+    }
+}
+
+/// A peer's commitment in a session without a coordinator, bound to the target it was asked to sign
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerAnnouncement {
+    // The hash of the signing target, see `SigningTarget::hash`
+    pub target_hash: Field<TestnetV0>,
+    pub commitment: SigningCommitment,
+}
+
+/// Every peer's signed announcement as received by the echoing peer, ordered by identifier
+///
+/// The announcements are forwarded with their owners' signatures, so a peer cannot echo a
+/// commitment its owner never announced.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommitmentEcho {
+    // The hash of the signing target, see `SigningTarget::hash`
+    pub target_hash: Field<TestnetV0>,
+    pub announcements: Vec<SignedEnvelope>,
+}
+
+/// The outcome of a signing session
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum AggregationResult {
+    Signature(Signature<TestnetV0>),
+    Request(Box<Request<TestnetV0>>),
+    // The session failed, with the reason
+    Failure(String),
+}
+
+/// The message of one protocol round
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum Payload {
+    // Keygen: the dealer sends each participant its key share
+    KeygenPackage(Box<KeygenPackage>),
+    // Round 1: a participant announces its signing commitments
+    CommitmentAnnouncement(Vec<SigningCommitment>),
+    // Peer mode: a participant announces its commitment for the target it was asked to sign
+    PeerAnnouncement(Box<PeerAnnouncement>),
+    // Peer mode: a participant echoes every announcement it received, to detect equivocation
+    CommitmentEcho(Box<CommitmentEcho>),
+    // Round 2: the coordinator sends the package to be signed
+    SigningRequest(Box<SigningRequest>),
+    // Round 2: a participant's nonce shares for a request
+    NonceShares(Box<NonceShares>),
+    // Round 2: a participant's partial signature
+    PartialSignature(PartialThresholdSignature),
+    // The coordinator announces the aggregated signature
+    AggregationResult(Box<AggregationResult>),
+}
+
+impl Payload {
+    // The name of the round message, for errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::KeygenPackage(..) => "keygen package",
+            Self::CommitmentAnnouncement(..) => "commitment announcement",
+            Self::PeerAnnouncement(..) => "peer announcement",
+            Self::CommitmentEcho(..) => "commitment echo",
+            Self::SigningRequest(..) => "signing request",
+            Self::NonceShares(..) => "nonce shares",
+            Self::PartialSignature(..) => "partial signature",
+            Self::AggregationResult(..) => "aggregation result",
+        }
+    }
+}
+
+/// A round message as sent between the processes of a signing session
+///
+/// The envelope binds the payload to the protocol version, the session, its sender and recipient,
+/// and the sender's sequence number, so messages cannot be replayed into another session or order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Envelope {
+    pub version: u16,
+    pub session_id: String,
+    pub sender: Party,
+    pub recipient: Party,
+    // The position of the message among the sender's messages in the session, starting at 0
+    pub sequence: u64,
+    pub payload: Payload,
+}
+
+impl Envelope {
+    pub fn new(
+        session_id: &str,
+        sender: Party,
+        recipient: Party,
+        sequence: u64,
+        payload: Payload,
+    ) -> Result<Self> {
+        let envelope = Self { version: PROTOCOL_VERSION, session_id: session_id.to_string(), sender, recipient, sequence, payload };
+        envelope.validate()?;
+
+        Ok(envelope)
+    }
+
+    /// Check that the payload may be sent from the sender to the recipient
+    ///
+    /// Participants may only send their own commitments, nonce shares and partial signatures.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.version == PROTOCOL_VERSION, "Unsupported protocol version {}, expected {PROTOCOL_VERSION}", self.version);
+        ensure!(!self.session_id.is_empty(), "Missing session id");
+        ensure!(self.sender != Party::Broadcast, "A {} cannot be sent by every party", self.payload.name());
+        ensure!(self.sender != self.recipient, "A {} cannot be sent by {} to itself", self.payload.name(), self.sender);
+
+        match (&self.payload, self.sender) {
+            (Payload::KeygenPackage(package), Party::Coordinator) => {
+                ensure!(
+                    self.recipient == Party::Participant(package.share.participant_id),
+                    "A keygen package must be sent to its participant only"
+                )
+            }
+            (Payload::SigningRequest(..) | Payload::AggregationResult(..), Party::Coordinator) => {}
+            (Payload::CommitmentAnnouncement(commitments), Party::Participant(sender)) => {
+                ensure!(
+                    commitments.iter().all(|commitment| commitment.participant_index == sender),
+                    "Participant {sender} announced commitments of another participant"
+                )
+            }
+            (Payload::PeerAnnouncement(announcement), Party::Participant(sender)) => {
+                ensure!(announcement.commitment.participant_index == sender, "Participant {sender} announced the commitment of another participant")
+            }
+            (Payload::CommitmentEcho(..), Party::Participant(..)) => {}
+            (Payload::NonceShares(shares), Party::Participant(sender)) => {
+                ensure!(
+                    shares.participant_index == sender && shares.gamma_shares.iter().all(|share| share.participant_index == sender),
+                    "Participant {sender} sent nonce shares of another participant"
+                )
+            }
+            (Payload::PartialSignature(partial_signature), Party::Participant(sender)) => {
+                ensure!(partial_signature.participant_index == sender, "Participant {sender} sent the partial signature of another participant")
+            }
+            (payload, sender) => bail!("A {} cannot be sent by {sender}", payload.name()),
+        }
+
+        Ok(())
+    }
+
+    // Encode the envelope as JSON
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    // Decode an envelope, rejecting unknown fields, other protocol versions and invalid senders
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let envelope: Self = serde_json::from_slice(bytes)?;
+        envelope.validate()?;
+
+        Ok(envelope)
+    }
+
+    /// Check that the envelope belongs to the session, is addressed to the receiver and follows the sender's last message
+    ///
+    /// `recipients` - The parties the receiver accepts messages for, such as itself and `Party::Broadcast`.
+    pub fn check_order(&self, session_id: &str, recipients: &[Party], last_sequence: Option<u64>) -> Result<()> {
+        ensure!(self.session_id == session_id, "Received a {} for session '{}' in session '{session_id}'", self.payload.name(), self.session_id);
+        ensure!(recipients.contains(&self.recipient), "The {} is addressed to {}", self.payload.name(), self.recipient);
+
+        let expected = last_sequence.map_or(0, |sequence| sequence + 1);
+        ensure!(
+            self.sequence == expected,
+            "Expected message {expected} from {}, found message {}", self.sender, self.sequence
+        );
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Party {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Coordinator => write!(f, "the coordinator"),
+            Self::Participant(identifier) => write!(f, "participant {identifier}"),
+            Self::Broadcast => write!(f, "every party"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    // A commitment announcement from participant `sender` to the coordinator, as JSON
+    fn announcement(sender: u64, sequence: u64, rng: &mut TestRng) -> Envelope {
+        let sender = Identifier::from_u64(sender).unwrap();
+        let commitment = SigningCommitment::from(sender, &SigningNonce::new(rng));
+
+        Envelope::new("session-1", Party::Participant(sender), Party::Coordinator, sequence, Payload::CommitmentAnnouncement(vec![commitment]))
+            .unwrap()
+    }
+
+    // Decode the envelope after editing its JSON encoding
+    fn decode_edited(envelope: &Envelope, edit: impl FnOnce(&mut serde_json::Value)) -> Result<Envelope> {
+        let mut json = serde_json::to_value(envelope).unwrap();
+        edit(&mut json);
+        Envelope::decode(&serde_json::to_vec(&json).unwrap())
+    }
+
+    #[test]
+    fn test_strict_decoding() {
+        let rng = &mut TestRng::default();
+        let envelope = announcement(1, 0, rng);
+        assert_eq!(Envelope::decode(&envelope.encode().unwrap()).unwrap(), envelope);
+
+        // Other protocol versions
+        assert!(decode_edited(&envelope, |json| json["version"] = (PROTOCOL_VERSION + 1).into()).is_err());
+        assert!(decode_edited(&envelope, |json| json["version"] = 0.into()).is_err());
+
+        // Unknown and missing fields, in the envelope and in the payload
+        assert!(decode_edited(&envelope, |json| json["priority"] = 1.into()).is_err());
+        assert!(decode_edited(&envelope, |json| json["payload"]["CommitmentAnnouncement"][0]["extra"] = 1.into()).is_err());
+        assert!(decode_edited(&envelope, |json| json["payload"] = serde_json::json!({ "Unknown": [] })).is_err());
+        assert!(decode_edited(&envelope, |json| {
+            json.as_object_mut().unwrap().remove("sequence");
+        }).is_err());
+        assert!(decode_edited(&envelope, |json| json["session_id"] = "".into()).is_err());
+        assert!(Envelope::decode(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_senders_only_post_their_own_payloads() {
+        let rng = &mut TestRng::default();
+        let envelope = announcement(1, 0, rng);
+        let (first, second) = (Identifier::from_u64(1).unwrap(), Identifier::from_u64(2).unwrap());
+
+        // Participant 2 posting participant 1's commitment
+        let other_sender = serde_json::to_value(Party::Participant(second)).unwrap();
+        assert!(decode_edited(&envelope, |json| json["sender"] = other_sender).is_err());
+        assert!(Envelope::new("session-1", Party::Participant(second), Party::Coordinator, 0, envelope.payload.clone()).is_err());
+
+        // Coordinator messages sent by a participant, and messages from every party or to the sender itself
+        let failure = Payload::AggregationResult(Box::new(AggregationResult::Failure("timeout".to_string())));
+        assert!(Envelope::new("session-1", Party::Coordinator, Party::Broadcast, 0, failure.clone()).is_ok());
+        assert!(Envelope::new("session-1", Party::Participant(first), Party::Broadcast, 0, failure).is_err());
+        assert!(Envelope::new("session-1", Party::Broadcast, Party::Coordinator, 0, envelope.payload.clone()).is_err());
+        assert!(Envelope::new("session-1", Party::Participant(first), Party::Participant(first), 0, envelope.payload.clone()).is_err());
+    }
+
+    #[test]
+    fn test_check_order() {
+        let rng = &mut TestRng::default();
+        let recipients = [Party::Coordinator];
+
+        assert!(announcement(1, 0, rng).check_order("session-1", &recipients, None).is_ok());
+        assert!(announcement(1, 3, rng).check_order("session-1", &recipients, Some(2)).is_ok());
+
+        // Skipped, replayed and reordered messages
+        assert!(announcement(1, 1, rng).check_order("session-1", &recipients, None).is_err());
+        assert!(announcement(1, 2, rng).check_order("session-1", &recipients, Some(2)).is_err());
+        assert!(announcement(1, 1, rng).check_order("session-1", &recipients, Some(2)).is_err());
+        assert!(announcement(1, 4, rng).check_order("session-1", &recipients, Some(2)).is_err());
+
+        // Another session or another recipient
+        assert!(announcement(1, 0, rng).check_order("session-2", &recipients, None).is_err());
+        let participant = Party::Participant(Identifier::from_u64(2).unwrap());
+        assert!(announcement(1, 0, rng).check_order("session-1", &[participant, Party::Broadcast], None).is_err());
+    }
+}
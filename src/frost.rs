@@ -1,21 +1,25 @@
 use std::collections::HashMap;
 
-use crate::{keys::*, preprocess::*, utils::*};
+use crate::{account::ThresholdAccount, generate_message::SigningMessage, hierarchy::*, identifier::Identifier, keys::*, preprocess::*, summary::Summarize, utils::*};
 
 use snarkvm_console_network::{Network, TestnetV0};
 use snarkvm_console_types::{Group, Scalar};
-use snarkvm_console_types_scalar::{anyhow, Field};
-use snarkvm_console_account::{compute_key::*, Address};
+use snarkvm_console_types_scalar::{anyhow, bail, ensure, Field, Result, Zero};
+use snarkvm_console_account::{compute_key::*, signature::*, Address};
 
-use rand::Error;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator for the binding values of message packages
+const MESSAGE_BINDING_DOMAIN: &str = "AleoFrostMessage";
 
 /// A partial signature made by each participant of the t-out-of-n secret
 /// sharing scheme where t is the threshold required to reconstruct
 /// a secret from a total of n shares
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PartialThresholdSignature {
-    // The index of the participant
-    pub participant_index: u64,
+    // The identifier of the participant
+    pub participant_index: Identifier,
     // The participant's signature over the message
     pub partial_signature: Scalar<TestnetV0>,
 }
@@ -34,47 +38,433 @@ pub struct PartialThresholdSignature {
 /// rho_i = binding value = H_1(i, message, signer's signing commitment)
 /// lambda_i = Lagrange coefficient
 /// c = challenge = H_2(group commitment, group public key, message)
+///
+/// Signers go through `SigningPackage::partial_sign` or a `SigningPolicy`, which check the
+/// message has a clear-signing summary first.
 impl PartialThresholdSignature {
-    pub fn new_partial_sig(
+    pub(crate) fn new_partial_sig(
         participant_signing_share: &SignerShare,
         signing_nonce: &SigningNonce,
         signing_commitments: Vec<SigningCommitment>,
         message: Vec<Field<TestnetV0>>,
         pr_sig: Group<TestnetV0>,
-    ) -> Result<Self, Error> {
+    ) -> Result<Self> {
+        // Calculate the Lagrange coefficient
+        let participant_indexes: Vec<Identifier> = signing_commitments.iter().map(|commitment| commitment.participant_index).collect();
+        let lambda_i = calculate_lagrange_coefficients(participant_signing_share.participant_index, &participant_indexes, None)?;
+
+        Self::sign(
+            participant_signing_share.participant_index,
+            lambda_i * participant_signing_share.secret_key.0,
+            signing_nonce,
+            signing_commitments,
+            message,
+            &participant_signing_share.group_public_key,
+            pr_sig,
+        )
+    }
+
+    /// Generate a new partial threshold signature for a weighted participant owning several shares.
+    ///
+    /// `participant_signing_shares` - All of the key shares owned by the participant.
+    /// `public_keys` - (public) The group's public keys including each participant's share indices.
+    ///
+    /// The signing commitments are made per participant, while the Lagrange coefficients are
+    /// computed over every share index owned by the signing participants:
+    /// z_i = d_i + (e_i * rho_i) - (Sum_j lambda_j * s_j) * c for j in the participant's indices
+    pub fn new_weighted_partial_sig(
+        participant_signing_shares: &[SignerShare],
+        signing_nonce: &SigningNonce,
+        signing_commitments: Vec<SigningCommitment>,
+        public_keys: &PublicKeys,
+        message: Vec<Field<TestnetV0>>,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<Self> {
+        let first = match participant_signing_shares.first() {
+            Some(first) => first,
+            None => bail!("A weighted partial signature requires at least one key share"),
+        };
+        ensure!(
+            participant_signing_shares.iter().all(|share| share.participant_id == first.participant_id),
+            "All key shares must be owned by the same participant"
+        );
+
+        // Collect the share indices of every signing participant
+        let mut share_indexes: Vec<Identifier> = Vec::new();
+        for commitment in &signing_commitments {
+            match public_keys.participant_shares.get(&commitment.participant_index) {
+                Some(indices) => share_indexes.extend(indices),
+                None => bail!("Participant {} is not part of the group", commitment.participant_index),
+            }
+        }
+
+        // The threshold is met by total weight rather than by the number of signers
+        ensure!(
+            share_indexes.len() >= first.commitment.len(),
+            "The signing participants hold a total weight of {}, below the threshold of {}",
+            share_indexes.len(),
+            first.commitment.len()
+        );
+
+        let mut weighted_secret = Scalar::<TestnetV0>::zero();
+        for share in participant_signing_shares {
+            let lambda_j = calculate_lagrange_coefficients(share.participant_index, &share_indexes, None)?;
+            weighted_secret = weighted_secret + lambda_j * share.secret_key.0;
+        }
+
+        Self::sign(first.participant_id, weighted_secret, signing_nonce, signing_commitments, message, &first.group_public_key, pr_sig)
+    }
+
+    /// Generate a new partial threshold signature for a member of a hierarchical access structure.
+    ///
+    /// `access_structure` - (public) The nested threshold policy the shares were generated for.
+    ///
+    /// lambda_i is replaced by the product of the Lagrange coefficients along the member's path:
+    /// z_i = d_i + (e_i * rho_i) - coeff_i * s_i * c
+    pub fn new_hierarchical_partial_sig(
+        participant_signing_share: &HierarchicalShare,
+        signing_nonce: &SigningNonce,
+        signing_commitments: Vec<SigningCommitment>,
+        access_structure: &AccessStructure,
+        message: Vec<Field<TestnetV0>>,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<Self> {
+        // The share must sit at the member's position in the access structure it is signed for
+        ensure!(
+            access_structure.path_of(participant_signing_share.participant_index)? == participant_signing_share.path,
+            "The share of participant {} does not belong to the access structure", participant_signing_share.participant_index
+        );
+
+        let participant_indexes: Vec<Identifier> = signing_commitments.iter().map(|commitment| commitment.participant_index).collect();
+        let coeff_i = calculate_lagrange_coefficients(
+            participant_signing_share.participant_index,
+            &participant_indexes,
+            Some(access_structure),
+        )?;
+
+        Self::sign(
+            participant_signing_share.participant_index,
+            coeff_i * participant_signing_share.secret_key.0,
+            signing_nonce,
+            signing_commitments,
+            message,
+            &participant_signing_share.group_public_key,
+            pr_sig,
+        )
+    }
+
+    // Compute z_i = d_i + (e_i * rho_i) - weighted_secret * c where weighted_secret is the participant's lambda_i * s_i
+    fn sign(
+        participant_index: Identifier,
+        weighted_secret: Scalar<TestnetV0>,
+        signing_nonce: &SigningNonce,
+        signing_commitments: Vec<SigningCommitment>,
+        message: Vec<Field<TestnetV0>>,
+        group_public_key: &GroupPublicKey,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<Self> {
         // Calculating rho_i in order to calculate R
-        let mut binding_values: HashMap<u64, Scalar<TestnetV0>> = HashMap::with_capacity(signing_commitments.len());
+        let binding_message = message_binding_message(&message);
+        let mut binding_values: HashMap<Identifier, Scalar<TestnetV0>> = HashMap::with_capacity(signing_commitments.len());
         for commitment in &signing_commitments {
-            let rho_i = calculate_binding_value(commitment.participant_index, &signing_commitments, &message);
+            let rho_i = calculate_binding_value(commitment.participant_index, &signing_commitments, &binding_message);
             binding_values.insert(commitment.participant_index, rho_i);
         }
 
         let signer_binding_value = binding_values
-            .get(&participant_signing_share.participant_index)
-            .ok_or_else(|| anyhow!("Missing binding value")).unwrap();
+            .get(&participant_index)
+            .ok_or_else(|| anyhow!("Missing binding value"))?;
 
         // Calculate the group commitment R as Product of (Di*Ei^rho_i)*...(Dn*En^rho_n)
         let group_commitment = calculate_group_commitment(&signing_commitments, &binding_values);
 
         // Generate the challenge for the signature
-        let address = Address::<TestnetV0>::try_from(ComputeKey::<TestnetV0>::try_from((participant_signing_share.group_public_key.0, pr_sig)).unwrap()).unwrap();
-        
-        let mut preimage = Vec::with_capacity(4 + message.len());
-        preimage.extend([group_commitment, participant_signing_share.group_public_key.0, pr_sig, *address].map(|point| point.to_x_coordinate()));
-        preimage.extend(message);
-
-        let challenge = Network::hash_to_scalar_psd8(&preimage).unwrap();
+        let challenge = calculate_challenge(group_commitment, group_public_key, pr_sig, &message)?;
 
-        // Calculate the Lagrange coefficient
-        let participant_indexes: Vec<u64> = signing_commitments.iter().map(|commitment| commitment.participant_index).collect();
-        let lambda_i = calculate_lagrange_coefficients(participant_signing_share.participant_index, &participant_indexes).unwrap();
+        Ok(Self::from_challenge(participant_index, weighted_secret, signing_nonce, *signer_binding_value, challenge))
+    }
 
-        // Calculating the response for the signature
+    // Calculating the response for the signature given the binding value and the challenge
+    pub(crate) fn from_challenge(
+        participant_index: Identifier,
+        weighted_secret: Scalar<TestnetV0>,
+        signing_nonce: &SigningNonce,
+        signer_binding_value: Scalar<TestnetV0>,
+        challenge: Scalar<TestnetV0>,
+    ) -> Self {
         // z_i = d_i + (e_i * rho_i) - lambda_i * s_i * c
         let partial_signature = signing_nonce.hiding
             + (signing_nonce.binding * signer_binding_value)
-            - (lambda_i * participant_signing_share.secret_key.0 * challenge);
-        
-        Ok(Self { participant_index: participant_signing_share.participant_index, partial_signature: partial_signature })
+            - (weighted_secret * challenge);
+
+        Self { participant_index, partial_signature }
+    }
+}
+
+/// A message to be threshold-signed by the group together with the signers' commitments
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningPackage {
+    // Each participant's public signing commitment
+    pub signing_commitments: Vec<SigningCommitment>,
+    // The message to be signed
+    pub message: SigningMessage,
+}
+
+impl SigningPackage {
+    pub fn new(
+        signing_commitments: Vec<SigningCommitment>,
+        message: SigningMessage,
+    ) -> Self {
+        Self { signing_commitments, message }
     }
-}
\ No newline at end of file
+
+    /// Generate a participant's partial signature, refusing packages without a clear-signing summary
+    ///
+    /// Bytes and fields messages are refused, they are only signed through a `SigningPolicy` that
+    /// allows their kind explicitly.
+    pub fn partial_sign(
+        &self,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        let kind = self.message.kind();
+        ensure!(!kind.is_raw(), "Refusing to sign an opaque {kind:?} message without a policy allowing it");
+
+        self.partial_sign_approved(participant_signing_share, signing_nonce, pr_sig)
+    }
+
+    /// Generate a weighted participant's partial signature over all of the key shares it owns
+    ///
+    /// The same packages are refused as with `partial_sign`.
+    pub fn partial_sign_weighted(
+        &self,
+        participant_signing_shares: &[SignerShare],
+        signing_nonce: &SigningNonce,
+        public_keys: &PublicKeys,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        let kind = self.message.kind();
+        ensure!(!kind.is_raw(), "Refusing to sign an opaque {kind:?} message without a policy allowing it");
+        self.summary()?;
+
+        PartialThresholdSignature::new_weighted_partial_sig(
+            participant_signing_shares,
+            signing_nonce,
+            self.signing_commitments.clone(),
+            public_keys,
+            self.message.to_fields()?,
+            pr_sig,
+        )
+    }
+
+    // Generate a participant's partial signature on a package whose summary the caller approved
+    pub(crate) fn partial_sign_approved(
+        &self,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        self.summary()?;
+
+        PartialThresholdSignature::new_partial_sig(
+            participant_signing_share,
+            signing_nonce,
+            self.signing_commitments.clone(),
+            self.message.to_fields()?,
+            pr_sig,
+        )
+    }
+
+    // Calculate the binding value rho_i of every signer of the package
+    fn binding_values(&self) -> Result<HashMap<Identifier, Scalar<TestnetV0>>> {
+        let binding_message = message_binding_message(&self.message.to_fields()?);
+
+        let mut binding_values: HashMap<Identifier, Scalar<TestnetV0>> = HashMap::with_capacity(self.signing_commitments.len());
+        for commitment in &self.signing_commitments {
+            let rho_i = calculate_binding_value(commitment.participant_index, &self.signing_commitments, &binding_message);
+            binding_values.insert(commitment.participant_index, rho_i);
+        }
+
+        Ok(binding_values)
+    }
+
+    // Calculate the challenge c = H(R, pk_sig, pr_sig, address, message) of the package
+    pub fn challenge(
+        &self,
+        group_public_key: &GroupPublicKey,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<Scalar<TestnetV0>> {
+        let group_commitment = calculate_group_commitment(&self.signing_commitments, &self.binding_values()?);
+
+        calculate_challenge(group_commitment, group_public_key, pr_sig, &self.message.to_fields()?)
+    }
+
+    /// Aggregate the partial signatures into a signature of the threshold account
+    ///
+    /// Every partial signature is checked against its signer's public key share first, and the
+    /// signature is checked against the account's address before it is returned.
+    pub fn aggregate(
+        &self,
+        account: &ThresholdAccount,
+        partial_signatures: &[PartialThresholdSignature],
+    ) -> Result<Signature<TestnetV0>> {
+        let challenge = self.challenge(account.group_public_key(), account.pr_sig)?;
+        verify_partial_signatures(partial_signatures, &self.signing_commitments, &self.binding_values()?, challenge, &account.public_keys)?;
+        let signature = aggregate_signature(challenge, partial_signatures, account.compute_key()?);
+
+        ensure!(signature.verify(&account.address()?, &self.message.to_fields()?), "The threshold signature failed to verify");
+
+        Ok(signature)
+    }
+}
+
+/// The message the binding values of a message package are computed over
+///
+/// [message domain, message...] -- request packages bind to [request domain, call...] instead, so
+/// commitments used for one kind of package cannot produce the group commitment of the other.
+pub fn message_binding_message(message: &[Field<TestnetV0>]) -> Vec<Field<TestnetV0>> {
+    let mut binding_message = Vec::with_capacity(1 + message.len());
+    binding_message.push(Field::<TestnetV0>::new_domain_separator(MESSAGE_BINDING_DOMAIN));
+    binding_message.extend(message);
+
+    binding_message
+}
+
+/// Generate the challenge of a threshold signature
+///
+/// c = H(g_r, pk_sig, pr_sig, address, message) where g_r is the group commitment, the same
+/// preimage an Aleo Signature is verified against.
+pub fn calculate_challenge(
+    group_commitment: Group<TestnetV0>,
+    group_public_key: &GroupPublicKey,
+    pr_sig: Group<TestnetV0>,
+    message: &[Field<TestnetV0>],
+) -> Result<Scalar<TestnetV0>> {
+    let address = Address::<TestnetV0>::try_from(ComputeKey::<TestnetV0>::try_from((group_public_key.0, pr_sig))?)?;
+
+    let mut preimage = Vec::with_capacity(4 + message.len());
+    preimage.extend([group_commitment, group_public_key.0, pr_sig, *address].map(|point| point.to_x_coordinate()));
+    preimage.extend(message);
+
+    Network::hash_to_scalar_psd8(&preimage)
+}
+
+/// Verify the signers' partial signatures against their public key shares before aggregating
+///
+/// G^z_i == D_i + rho_i * E_i - c * Sum_j lambda_j * Y_j for every signer i, where j runs over the
+/// share indices owned by the signer and lambda_j is computed over every share index of the
+/// signers, so weighted participants are verified against all of their shares. A signer sending
+/// a bad partial signature is identified instead of only failing the aggregated signature.
+pub fn verify_partial_signatures(
+    partial_signatures: &[PartialThresholdSignature],
+    signing_commitments: &[SigningCommitment],
+    binding_values: &HashMap<Identifier, Scalar<TestnetV0>>,
+    challenge: Scalar<TestnetV0>,
+    public_keys: &PublicKeys,
+) -> Result<()> {
+    ensure!(
+        partial_signatures.len() == signing_commitments.len(),
+        "Expected partial signatures from {} signers, found {}", signing_commitments.len(), partial_signatures.len()
+    );
+
+    // Collect the share indices of every signing participant
+    let mut share_indexes: Vec<Identifier> = Vec::new();
+    for commitment in signing_commitments {
+        match public_keys.participant_shares.get(&commitment.participant_index) {
+            Some(indices) => share_indexes.extend(indices),
+            None => bail!("Participant {} is not part of the group", commitment.participant_index),
+        }
+    }
+
+    for commitment in signing_commitments {
+        let participant_index = commitment.participant_index;
+
+        let partial_signature = match partial_signatures.iter().find(|partial| partial.participant_index == participant_index) {
+            Some(partial_signature) => partial_signature,
+            None => bail!("Missing the partial signature of participant {participant_index}"),
+        };
+        let rho_i = match binding_values.get(&participant_index) {
+            Some(rho_i) => rho_i,
+            None => bail!("Missing binding value"),
+        };
+
+        // Sum_j lambda_j * Y_j over the participant's share indices
+        let mut weighted_key = Group::<TestnetV0>::zero();
+        for share_index in &public_keys.participant_shares[&participant_index] {
+            let public_key = match public_keys.public_keys.get(share_index) {
+                Some(public_key) => public_key,
+                None => bail!("Share {share_index} of participant {participant_index} has no public key"),
+            };
+            let lambda_j = calculate_lagrange_coefficients(*share_index, &share_indexes, None)?;
+            weighted_key = weighted_key + public_key.0 * lambda_j;
+        }
+
+        ensure!(
+            Network::g_scalar_multiply(&partial_signature.partial_signature)
+                == commitment.hiding + commitment.binding * rho_i - weighted_key * challenge,
+            "Invalid partial signature from participant {participant_index}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Aggregate the partial signatures of the signers into an Aleo Signature
+///
+/// The response is the sum of the partial signatures z = Sum z_i, and the signature
+/// is published as (challenge, response, compute key).
+pub fn aggregate_signature(
+    challenge: Scalar<TestnetV0>,
+    partial_signatures: &[PartialThresholdSignature],
+    compute_key: ComputeKey<TestnetV0>,
+) -> Signature<TestnetV0> {
+    let mut response = Scalar::<TestnetV0>::zero();
+    for partial_signature in partial_signatures {
+        response = response + partial_signature.partial_signature;
+    }
+
+    Signature::<TestnetV0>::from((challenge, response, compute_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_weighted_signing() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (a, b) = (Identifier::from_u64(10).unwrap(), Identifier::from_u64(20).unwrap());
+
+        // 2-of-{A: 2, B: 1}
+        let (shares, public_keys) = trusted_keygen_weighted(&[(a, 2), (b, 1)], 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys.clone(), &private_key).unwrap();
+        let shares_of = |owner: Identifier| shares.iter().filter(|share| share.participant_id == owner).cloned().collect::<Vec<_>>();
+        let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+
+        // A meets the threshold alone, and A signs together with B
+        for signers in [vec![a], vec![a, b]] {
+            let nonces: Vec<SigningNonce> = signers.iter().map(|_| SigningNonce::new(rng)).collect();
+            let commitments = signers.iter().zip(&nonces).map(|(signer, nonce)| SigningCommitment::from(*signer, nonce)).collect();
+            let package = SigningPackage::new(commitments, message.clone());
+
+            let partial_signatures: Vec<PartialThresholdSignature> = signers
+                .iter()
+                .zip(&nonces)
+                .map(|(signer, nonce)| package.partial_sign_weighted(&shares_of(*signer), nonce, &public_keys, account.pr_sig).unwrap())
+                .collect();
+            let signature = package.aggregate(&account, &partial_signatures).unwrap();
+            assert!(signature.verify(&account.address().unwrap(), &message.to_fields().unwrap()));
+        }
+
+        // B alone is below the threshold
+        let nonce = SigningNonce::new(rng);
+        let package = SigningPackage::new(vec![SigningCommitment::from(b, &nonce)], message);
+        assert!(package.partial_sign_weighted(&shares_of(b), &nonce, &public_keys, account.pr_sig).is_err());
+    }
+}
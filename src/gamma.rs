@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+
+use snarkvm_console_account::Address;
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Field, Group, Scalar};
+use snarkvm_console_types_scalar::{bail, ensure, Result, Zero};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{dleq::*, identifier::Identifier, keys::*, preprocess::*, utils::*};
+
+// Compute the generator H of a record as HashToGroup(serial number domain, commitment)
+pub fn record_generator(commitment: Field<TestnetV0>) -> Result<Group<TestnetV0>> {
+    TestnetV0::hash_to_group_psd2(&[TestnetV0::serial_number_domain(), commitment])
+}
+
+/// A participant's share of the gamma of a record spent by the threshold account
+///
+/// Spending a record requires gamma = H^sk_sig and r * H in the request, with H derived from the
+/// record commitment. Each signer publishes its shares of both with DLEQ proofs:
+/// - gamma_i = H^s_i, proven against the participant's public key G^s_i
+/// - (H^d_i, H^e_i), proven against the participant's signing commitment (D_i, E_i)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GammaShare {
+    // The identifier of the participant
+    pub participant_index: Identifier,
+    // The commitment of the record being spent
+    pub commitment: Field<TestnetV0>,
+    // The participant's gamma share H^s_i
+    pub gamma: Group<TestnetV0>,
+    // The proof that log_H(gamma_i) == log_G(Y_i)
+    pub gamma_proof: DleqProof,
+    // The hiding nonce times H -- H^d_i
+    pub hiding: Group<TestnetV0>,
+    // The proof that log_H(H^d_i) == log_G(D_i)
+    pub hiding_proof: DleqProof,
+    // The binding nonce times H -- H^e_i
+    pub binding: Group<TestnetV0>,
+    // The proof that log_H(H^e_i) == log_G(E_i)
+    pub binding_proof: DleqProof,
+}
+
+impl GammaShare {
+    // Generate the participant's gamma share for the record with the given commitment
+    pub fn new<R: Rng>(
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        commitment: Field<TestnetV0>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let h = record_generator(commitment)?;
+
+        Ok(Self {
+            participant_index: participant_signing_share.participant_index,
+            commitment,
+            gamma: h * participant_signing_share.secret_key.0,
+            gamma_proof: DleqProof::prove(&participant_signing_share.secret_key.0, h, rng)?,
+            hiding: h * signing_nonce.hiding,
+            hiding_proof: DleqProof::prove(&signing_nonce.hiding, h, rng)?,
+            binding: h * signing_nonce.binding,
+            binding_proof: DleqProof::prove(&signing_nonce.binding, h, rng)?,
+        })
+    }
+
+    // Verify the DLEQ proofs against the participant's public key and signing commitment
+    pub fn verify(
+        &self,
+        public_key: &SignerPublicKey,
+        signing_commitment: &SigningCommitment,
+    ) -> Result<()> {
+        let h = record_generator(self.commitment)?;
+
+        ensure!(
+            self.gamma_proof.verify(h, public_key.0, self.gamma),
+            "Invalid gamma share proof from participant {}", self.participant_index
+        );
+        ensure!(
+            self.hiding_proof.verify(h, signing_commitment.hiding, self.hiding)
+                && self.binding_proof.verify(h, signing_commitment.binding, self.binding),
+            "Invalid nonce share proof from participant {}", self.participant_index
+        );
+
+        Ok(())
+    }
+}
+
+/// A participant's shares of the request nonce r, sent for every request before partial signing
+///
+/// The transition view key is tvk = (r * address).x. Without the account's view key, each signer
+/// publishes (address^d_i, address^e_i) with DLEQ proofs against its signing commitment (D_i, E_i),
+/// so r * address = Sum (address^d_i + rho_i * address^e_i) matches R = Sum (D_i + rho_i * E_i).
+/// Requests spending records additionally carry one `GammaShare` per record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NonceShares {
+    // The identifier of the participant
+    pub participant_index: Identifier,
+    // The hiding nonce times the address -- address^d_i
+    pub hiding: Group<TestnetV0>,
+    // The proof that log_address(address^d_i) == log_G(D_i)
+    pub hiding_proof: DleqProof,
+    // The binding nonce times the address -- address^e_i
+    pub binding: Group<TestnetV0>,
+    // The proof that log_address(address^e_i) == log_G(E_i)
+    pub binding_proof: DleqProof,
+    // The participant's gamma shares for the records spent by the request
+    pub gamma_shares: Vec<GammaShare>,
+}
+
+impl NonceShares {
+    // Generate the participant's nonce shares for the account address and the records with the given commitments
+    pub fn new<R: Rng>(
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        address: &Address<TestnetV0>,
+        record_commitments: &[Field<TestnetV0>],
+        rng: &mut R,
+    ) -> Result<Self> {
+        let gamma_shares = record_commitments
+            .iter()
+            .map(|commitment| GammaShare::new(participant_signing_share, signing_nonce, *commitment, rng))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            participant_index: participant_signing_share.participant_index,
+            hiding: **address * signing_nonce.hiding,
+            hiding_proof: DleqProof::prove(&signing_nonce.hiding, **address, rng)?,
+            binding: **address * signing_nonce.binding,
+            binding_proof: DleqProof::prove(&signing_nonce.binding, **address, rng)?,
+            gamma_shares,
+        })
+    }
+
+    // Verify the DLEQ proofs against the participant's public key and signing commitment
+    pub fn verify(
+        &self,
+        address: &Address<TestnetV0>,
+        public_key: &SignerPublicKey,
+        signing_commitment: &SigningCommitment,
+    ) -> Result<()> {
+        ensure!(
+            signing_commitment.participant_index == self.participant_index,
+            "The nonce shares of participant {} are checked against another participant's commitment", self.participant_index
+        );
+        ensure!(
+            self.hiding_proof.verify(**address, signing_commitment.hiding, self.hiding)
+                && self.binding_proof.verify(**address, signing_commitment.binding, self.binding),
+            "Invalid transition key share proof from participant {}", self.participant_index
+        );
+        for gamma_share in &self.gamma_shares {
+            ensure!(
+                gamma_share.participant_index == self.participant_index,
+                "The nonce shares of participant {} hold a gamma share of participant {}", self.participant_index, gamma_share.participant_index
+            );
+            gamma_share.verify(public_key, signing_commitment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Combine the signers' nonce shares into the transition public key times the address, r * address
+///
+/// Every signer must have sent exactly one set of nonce shares, each checked against the
+/// participant's public key and signing commitment first.
+pub fn combine_transition_key_shares(
+    address: &Address<TestnetV0>,
+    nonce_shares: &[NonceShares],
+    signing_commitments: &[SigningCommitment],
+    binding_values: &HashMap<Identifier, Scalar<TestnetV0>>,
+    public_keys: &PublicKeys,
+) -> Result<Group<TestnetV0>> {
+    ensure!(
+        nonce_shares.len() == signing_commitments.len(),
+        "Expected nonce shares from {} signers, found {}", signing_commitments.len(), nonce_shares.len()
+    );
+
+    let mut r_address = Group::<TestnetV0>::zero();
+    for signing_commitment in signing_commitments {
+        let participant_index = signing_commitment.participant_index;
+
+        let nonce_share = match nonce_shares.iter().find(|share| share.participant_index == participant_index) {
+            Some(nonce_share) => nonce_share,
+            None => bail!("Missing nonce shares from participant {participant_index}"),
+        };
+        let public_key = match public_keys.public_keys.get(&participant_index) {
+            Some(public_key) => public_key,
+            None => bail!("Participant {participant_index} is not part of the group"),
+        };
+        nonce_share.verify(address, public_key, signing_commitment)?;
+
+        let rho_i = match binding_values.get(&participant_index) {
+            Some(rho_i) => rho_i,
+            None => bail!("Missing binding value"),
+        };
+
+        r_address = r_address + nonce_share.hiding + nonce_share.binding * rho_i;
+    }
+
+    Ok(r_address)
+}
+
+/// Combine the gamma shares of the signers for one record into (gamma, r * H)
+///
+/// gamma = Sum lambda_i * gamma_i = H^sk_sig
+/// r * H = Sum (H^d_i + rho_i * H^e_i), matching the group commitment R = Sum (D_i + rho_i * E_i)
+///
+/// Every share is checked against the participant's public key and signing commitment first.
+pub fn combine_gamma_shares(
+    commitment: Field<TestnetV0>,
+    gamma_shares: &[GammaShare],
+    signing_commitments: &[SigningCommitment],
+    binding_values: &HashMap<Identifier, Scalar<TestnetV0>>,
+    public_keys: &PublicKeys,
+) -> Result<(Group<TestnetV0>, Group<TestnetV0>)> {
+    let participant_indexes: Vec<Identifier> = signing_commitments.iter().map(|commitment| commitment.participant_index).collect();
+
+    let mut gamma = Group::<TestnetV0>::zero();
+    let mut h_r = Group::<TestnetV0>::zero();
+    for signing_commitment in signing_commitments {
+        let participant_index = signing_commitment.participant_index;
+
+        let gamma_share = match gamma_shares.iter().find(|share| share.participant_index == participant_index && share.commitment == commitment) {
+            Some(gamma_share) => gamma_share,
+            None => bail!("Missing gamma share from participant {participant_index}"),
+        };
+        let public_key = match public_keys.public_keys.get(&participant_index) {
+            Some(public_key) => public_key,
+            None => bail!("Participant {participant_index} is not part of the group"),
+        };
+        gamma_share.verify(public_key, signing_commitment)?;
+
+        let lambda_i = calculate_lagrange_coefficients(participant_index, &participant_indexes, None)?;
+        let rho_i = match binding_values.get(&participant_index) {
+            Some(rho_i) => rho_i,
+            None => bail!("Missing binding value"),
+        };
+
+        gamma = gamma + gamma_share.gamma * lambda_i;
+        h_r = h_r + gamma_share.hiding + gamma_share.binding * rho_i;
+    }
+
+    Ok((gamma, h_r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::{TestRng, Uniform};
+
+    #[test]
+    fn test_gamma_shares() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let signers = [&shares[0], &shares[2]];
+        let nonces: Vec<SigningNonce> = signers.iter().map(|_| SigningNonce::new(rng)).collect();
+        let commitments: Vec<SigningCommitment> =
+            signers.iter().zip(&nonces).map(|(share, nonce)| SigningCommitment::from(share.participant_index, nonce)).collect();
+        let binding_values: HashMap<Identifier, Scalar<TestnetV0>> =
+            signers.iter().map(|share| (share.participant_index, Scalar::<TestnetV0>::rand(rng))).collect();
+
+        let record = Field::<TestnetV0>::rand(rng);
+        let h = record_generator(record).unwrap();
+        let gamma_shares: Vec<GammaShare> =
+            signers.iter().zip(&nonces).map(|(share, nonce)| GammaShare::new(share, nonce, record, rng).unwrap()).collect();
+
+        // gamma = H^sk_sig and r * H matches the nonces behind the group commitment
+        let (gamma, h_r) = combine_gamma_shares(record, &gamma_shares, &commitments, &binding_values, &public_keys).unwrap();
+        assert_eq!(gamma, h * private_key.sk_sig());
+        let r = signers
+            .iter()
+            .zip(&nonces)
+            .fold(Scalar::<TestnetV0>::zero(), |r, (share, nonce)| r + nonce.hiding + nonce.binding * binding_values[&share.participant_index]);
+        assert_eq!(h_r, h * r);
+
+        // A gamma share of another secret, or for another record, or missing
+        let combine = |gamma_shares: &[GammaShare]| combine_gamma_shares(record, gamma_shares, &commitments, &binding_values, &public_keys);
+        let mut forged = gamma_shares.clone();
+        forged[0].gamma = h * shares[1].secret_key.0;
+        assert!(combine(&forged).is_err());
+        let mut forged = gamma_shares.clone();
+        forged[1].hiding = forged[1].hiding + Group::<TestnetV0>::generator();
+        assert!(combine(&forged).is_err());
+        let mut forged = gamma_shares.clone();
+        forged[0] = GammaShare::new(signers[0], &nonces[0], Field::<TestnetV0>::rand(rng), rng).unwrap();
+        assert!(combine(&forged).is_err());
+        assert!(combine(&gamma_shares[..1]).is_err());
+
+        // A share checked against another participant's public key or commitment
+        let own_key = &public_keys.public_keys[&signers[0].participant_index];
+        let other_key = &public_keys.public_keys[&signers[1].participant_index];
+        assert!(gamma_shares[0].verify(own_key, &commitments[0]).is_ok());
+        assert!(gamma_shares[0].verify(other_key, &commitments[0]).is_err());
+        assert!(gamma_shares[0].verify(own_key, &commitments[1]).is_err());
+    }
+
+    #[test]
+    fn test_nonce_shares() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let address = Address::try_from(&private_key).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let signers = [&shares[0], &shares[1]];
+        let nonces: Vec<SigningNonce> = signers.iter().map(|_| SigningNonce::new(rng)).collect();
+        let commitments: Vec<SigningCommitment> =
+            signers.iter().zip(&nonces).map(|(share, nonce)| SigningCommitment::from(share.participant_index, nonce)).collect();
+        let binding_values: HashMap<Identifier, Scalar<TestnetV0>> =
+            signers.iter().map(|share| (share.participant_index, Scalar::<TestnetV0>::rand(rng))).collect();
+
+        let records = [Field::<TestnetV0>::rand(rng)];
+        let nonce_shares: Vec<NonceShares> =
+            signers.iter().zip(&nonces).map(|(share, nonce)| NonceShares::new(share, nonce, &address, &records, rng).unwrap()).collect();
+        let combine = |nonce_shares: &[NonceShares]| combine_transition_key_shares(&address, nonce_shares, &commitments, &binding_values, &public_keys);
+        assert!(combine(&nonce_shares).is_ok());
+
+        // Shares for another address, with a forged point, or with another participant's gamma share
+        let other = Address::try_from(&PrivateKey::<TestnetV0>::new(rng).unwrap()).unwrap();
+        let mut forged = nonce_shares.clone();
+        forged[0] = NonceShares::new(signers[0], &nonces[0], &other, &records, rng).unwrap();
+        assert!(combine(&forged).is_err());
+        let mut forged = nonce_shares.clone();
+        forged[1].binding = forged[1].binding + Group::<TestnetV0>::generator();
+        assert!(combine(&forged).is_err());
+        let mut forged = nonce_shares.clone();
+        forged[0].gamma_shares = forged[1].gamma_shares.clone();
+        assert!(combine(&forged).is_err());
+        assert!(combine(&nonce_shares[..1]).is_err());
+        assert!(combine(&[nonce_shares[0].clone(), nonce_shares[0].clone()]).is_err());
+
+        let public_key = &public_keys.public_keys[&signers[0].participant_index];
+        assert!(nonce_shares[0].verify(&address, public_key, &commitments[1]).is_err());
+    }
+}
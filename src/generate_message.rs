@@ -1 +1,169 @@
-// Generating message for multisig signing
\ No newline at end of file
+// Generating message for multisig signing
+use snarkvm_console_account::{FromFields, ToFields};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::{Literal, Plaintext, Value};
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{bail, FromBits, Result, SizeInDataBits, ToBits};
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+// The kind of payload a signing message was built from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageKind {
+    Value,
+    Plaintext,
+    Literal,
+    Bytes,
+    Fields,
+}
+
+impl MessageKind {
+    /// Whether the kind carries opaque data the signer cannot read
+    ///
+    /// Raw bytes and fields can encode anything, including the challenge preimage of a request,
+    /// so they are only signed when the signer's policy lists the kind explicitly.
+    pub fn is_raw(&self) -> bool {
+        matches!(self, Self::Bytes | Self::Fields)
+    }
+}
+
+/// A typed message to be signed by the group
+///
+/// Every signer converts the message to the same `Vec<Field>` with `to_fields`, which is the
+/// message signed by `SigningPackage::partial_sign`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigningMessage {
+    // An Aleo value, signed as Value::to_fields
+    Value(Value<TestnetV0>),
+    // An Aleo plaintext (struct, array or literal), signed as Plaintext::to_fields
+    Plaintext(Plaintext<TestnetV0>),
+    // An Aleo literal, signed as the fields of the equivalent plaintext
+    Literal(Literal<TestnetV0>),
+    // Raw bytes, packed little-endian into fields the same way as Signature::verify_bytes
+    Bytes(Vec<u8>),
+    // Field elements, signed as is
+    Fields(Vec<Field<TestnetV0>>),
+}
+
+impl SigningMessage {
+    // Build a message from an Aleo value
+    pub fn from_value(value: Value<TestnetV0>) -> Self {
+        Self::Value(value)
+    }
+
+    // Build a message from an Aleo plaintext
+    pub fn from_plaintext(plaintext: Plaintext<TestnetV0>) -> Self {
+        Self::Plaintext(plaintext)
+    }
+
+    // Build a message from an Aleo literal
+    pub fn from_literal(literal: Literal<TestnetV0>) -> Self {
+        Self::Literal(literal)
+    }
+
+    // Build a message from raw bytes
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
+
+    // Build a message from field elements
+    pub fn from_fields(fields: Vec<Field<TestnetV0>>) -> Self {
+        Self::Fields(fields)
+    }
+
+    // Build a message by parsing an Aleo value such as "{ recipient: aleo1..., amount: 10u128 }"
+    pub fn parse_value(value: &str) -> Result<Self> {
+        Ok(Self::Value(Value::<TestnetV0>::from_str(value)?))
+    }
+
+    // The kind of payload the message was built from
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Self::Value(..) => MessageKind::Value,
+            Self::Plaintext(..) => MessageKind::Plaintext,
+            Self::Literal(..) => MessageKind::Literal,
+            Self::Bytes(..) => MessageKind::Bytes,
+            Self::Fields(..) => MessageKind::Fields,
+        }
+    }
+
+    /// Canonical conversion of the message into the fields signed by the group
+    ///
+    /// - Value and Plaintext use snarkVM's `to_fields`
+    /// - A literal is signed as `Plaintext::Literal`, so it matches the same literal given as a value
+    /// - Bytes are converted to little-endian bits and packed into fields of `Field::size_in_data_bits`
+    pub fn to_fields(&self) -> Result<Vec<Field<TestnetV0>>> {
+        match self {
+            Self::Value(value) => value.to_fields(),
+            Self::Plaintext(plaintext) => plaintext.to_fields(),
+            Self::Literal(literal) => Plaintext::from(literal.clone()).to_fields(),
+            Self::Bytes(bytes) => bytes_to_fields(bytes),
+            Self::Fields(fields) => Ok(fields.clone()),
+        }
+    }
+
+    /// Decodes signed fields back into a typed message for display
+    ///
+    /// Only plaintext values can be decoded, records are rejected. Bytes are recovered up to
+    /// trailing zero bytes, which cannot be told apart from the padding of the last field.
+    pub fn decode(fields: &[Field<TestnetV0>], kind: MessageKind) -> Result<Self> {
+        match kind {
+            MessageKind::Value => Ok(Self::Value(Value::Plaintext(Plaintext::from_fields(fields)?))),
+            MessageKind::Plaintext => Ok(Self::Plaintext(Plaintext::from_fields(fields)?)),
+            MessageKind::Literal => match Plaintext::from_fields(fields)? {
+                Plaintext::Literal(literal, ..) => Ok(Self::Literal(literal)),
+                plaintext => bail!("Expected a literal, found '{plaintext}'"),
+            },
+            MessageKind::Bytes => Ok(Self::Bytes(fields_to_bytes(fields))),
+            MessageKind::Fields => Ok(Self::Fields(fields.to_vec())),
+        }
+    }
+}
+
+// Pack little-endian bits of the bytes into fields of `Field::size_in_data_bits` bits
+pub(crate) fn bytes_to_fields(bytes: &[u8]) -> Result<Vec<Field<TestnetV0>>> {
+    bytes.to_bits_le().chunks(Field::<TestnetV0>::size_in_data_bits()).map(Field::from_bits_le).collect()
+}
+
+// Unpack fields into bytes, dropping the zero padding of the last field
+pub(crate) fn fields_to_bytes(fields: &[Field<TestnetV0>]) -> Vec<u8> {
+    let bits: Vec<bool> = fields
+        .iter()
+        .flat_map(|field| field.to_bits_le().into_iter().take(Field::<TestnetV0>::size_in_data_bits()))
+        .collect();
+
+    let mut bytes: Vec<u8> = bits
+        .chunks_exact(8)
+        .map(|byte| byte.iter().rev().fold(0u8, |acc, bit| (acc << 1) | *bit as u8))
+        .collect();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+
+    bytes
+}
+
+impl fmt::Display for SigningMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Value(value) => write!(f, "{value}"),
+            Self::Plaintext(plaintext) => write!(f, "{plaintext}"),
+            Self::Literal(literal) => write!(f, "{literal}"),
+            Self::Bytes(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => write!(f, "{text:?}"),
+                Err(..) => write!(f, "0x{}", bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>()),
+            },
+            Self::Fields(fields) => {
+                write!(f, "[")?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{field}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Group, Scalar};
+use snarkvm_console_types_scalar::{bail, ensure, Error, One, Result, Uniform, Zero};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{identifier::Identifier, keys::*, utils::*};
+
+/// The largest number of members an access structure can have, the same bound as `trusted_keygen`
+pub const MAX_MEMBERS: u64 = u16::MAX as u64;
+/// The deepest nesting of threshold nodes accepted by the parser
+const MAX_DEPTH: usize = 32;
+
+/// A hierarchical (nested threshold) access structure
+///
+/// Policies are written as a tree description, for example
+/// "2 of {2 of 3, 1 of 2, 1 of 1}" is satisfied by any 2 of the three teams, where the first
+/// team needs 2 of its 3 members, the second 1 of its 2 members and the third its only member.
+/// "t of n" is shorthand for a threshold over n individual members.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessStructure {
+    // A single signer holding a leaf share
+    Member,
+    // A node satisfied once `threshold` of its children are satisfied
+    Threshold { threshold: u64, children: Vec<AccessStructure> },
+}
+
+impl AccessStructure {
+    // The number of signers (leaves) in the access structure
+    pub fn num_members(&self) -> u64 {
+        match self {
+            Self::Member => 1,
+            Self::Threshold { children, .. } => children.iter().map(|child| child.num_members()).sum(),
+        }
+    }
+
+    // Check that the structure has at most MAX_MEMBERS members and every threshold node has between 1 and its number of children as threshold
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.num_members() <= MAX_MEMBERS, "The access structure has more than {MAX_MEMBERS} members");
+        self.validate_node()
+    }
+
+    fn validate_node(&self) -> Result<()> {
+        if let Self::Threshold { threshold, children } = self {
+            ensure!(!children.is_empty(), "A threshold node must have at least one child");
+            ensure!(
+                *threshold >= 1 && *threshold <= children.len() as u64,
+                "The threshold {threshold} must be between 1 and the number of children {}", children.len()
+            );
+            for child in children {
+                child.validate_node()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compute the coefficient of every signing member such that Sum coeff_j * s_j = secret
+    ///
+    /// The coefficient of a member is the product of the Lagrange coefficients along its path,
+    /// where each Lagrange coefficient is computed over the satisfied children of the node.
+    /// Members of subtrees that are not satisfied are left out (their coefficient is zero).
+    /// Returns None if the signing members do not satisfy the access structure.
+    pub(crate) fn lagrange_coefficients(&self, signing_members: &[Identifier]) -> Result<Option<HashMap<Identifier, Scalar<TestnetV0>>>> {
+        let mut next_member = 1u64;
+        self.node_coefficients(signing_members, &mut next_member)
+    }
+
+    /// The position of a member at each level of the access structure
+    ///
+    /// Members are numbered 1, 2, ... in the order of the tree description, like in `hierarchical_keygen`.
+    pub fn path_of(&self, member: Identifier) -> Result<Vec<u64>> {
+        let mut next_member = 1u64;
+        let mut path = Vec::new();
+        match self.find_member(member, &mut next_member, &mut path)? {
+            true => Ok(path),
+            false => bail!("Participant {member} is not a member of the access structure"),
+        }
+    }
+
+    fn find_member(&self, member: Identifier, next_member: &mut u64, path: &mut Vec<u64>) -> Result<bool> {
+        match self {
+            Self::Member => {
+                let found = Identifier::from_u64(*next_member)? == member;
+                *next_member += 1;
+                Ok(found)
+            }
+            Self::Threshold { children, .. } => {
+                for (position, child) in children.iter().enumerate() {
+                    path.push(position as u64 + 1);
+                    if child.find_member(member, next_member, path)? {
+                        return Ok(true);
+                    }
+                    path.pop();
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn node_coefficients(&self, signing_members: &[Identifier], next_member: &mut u64) -> Result<Option<HashMap<Identifier, Scalar<TestnetV0>>>> {
+        match self {
+            Self::Member => {
+                let member = Identifier::from_u64(*next_member)?;
+                *next_member += 1;
+
+                match signing_members.contains(&member) {
+                    true => Ok(Some(HashMap::from([(member, Scalar::<TestnetV0>::one())]))),
+                    false => Ok(None),
+                }
+            }
+            Self::Threshold { threshold, children } => {
+                let mut satisfied = Vec::with_capacity(children.len());
+                for (position, child) in children.iter().enumerate() {
+                    if let Some(coefficients) = child.node_coefficients(signing_members, next_member)? {
+                        satisfied.push((Identifier::from_u64(position as u64 + 1)?, coefficients));
+                    }
+                }
+
+                if (satisfied.len() as u64) < *threshold {
+                    return Ok(None);
+                }
+
+                let positions: Vec<Identifier> = satisfied.iter().map(|(position, _)| *position).collect();
+                let mut result = HashMap::new();
+                for (position, coefficients) in satisfied {
+                    let lambda = calculate_lagrange_coefficients(position, &positions, None)?;
+                    result.extend(coefficients.into_iter().map(|(member, coefficient)| (member, lambda * coefficient)));
+                }
+
+                Ok(Some(result))
+            }
+        }
+    }
+}
+
+impl FromStr for AccessStructure {
+    type Err = Error;
+
+    fn from_str(policy: &str) -> Result<Self> {
+        let tokens = tokenize(policy)?;
+        let mut position = 0;
+        let mut num_members = 0;
+        let structure = parse_node(&tokens, &mut position, &mut num_members, 0)?;
+        ensure!(position == tokens.len(), "Unexpected trailing input in the access structure '{policy}'");
+        structure.validate()?;
+
+        Ok(structure)
+    }
+}
+
+impl fmt::Display for AccessStructure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Member => write!(f, "1 of 1"),
+            Self::Threshold { threshold, children } if children.iter().all(|child| *child == Self::Member) => {
+                write!(f, "{threshold} of {}", children.len())
+            }
+            Self::Threshold { threshold, children } => {
+                write!(f, "{threshold} of {{")?;
+                for (i, child) in children.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{child}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Split a tree description into numbers, "of", braces and commas
+fn tokenize(policy: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = policy.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' | ',' => tokens.push(c.to_string()),
+            c if c.is_whitespace() => continue,
+            c if c.is_ascii_alphanumeric() => {
+                let mut token = c.to_string();
+                while let Some(next) = chars.peek().filter(|next| next.is_ascii_alphanumeric()) {
+                    token.push(*next);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+            _ => bail!("Unexpected character '{c}' in the access structure"),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// node := NUMBER "of" ( NUMBER | "{" node ("," node)* "}" )
+//
+// `total_members` counts the members parsed so far, so a description cannot allocate more than MAX_MEMBERS members
+fn parse_node(tokens: &[String], position: &mut usize, total_members: &mut u64, depth: usize) -> Result<AccessStructure> {
+    ensure!(depth < MAX_DEPTH, "The access structure is nested deeper than {MAX_DEPTH} levels");
+    let threshold = parse_number(tokens, position)?;
+    ensure!(tokens.get(*position).map(String::as_str) == Some("of"), "Expected 'of' after the threshold {threshold}");
+    *position += 1;
+
+    if tokens.get(*position).map(String::as_str) != Some("{") {
+        let num_members = parse_number(tokens, position)?;
+        ensure!(
+            threshold >= 1 && threshold <= num_members,
+            "The threshold {threshold} must be between 1 and the number of members {num_members}"
+        );
+        ensure!(
+            num_members <= MAX_MEMBERS - *total_members,
+            "The access structure has more than {MAX_MEMBERS} members"
+        );
+        *total_members += num_members;
+
+        // A single member is written as "1 of 1"
+        if threshold == 1 && num_members == 1 {
+            return Ok(AccessStructure::Member);
+        }
+        return Ok(AccessStructure::Threshold { threshold, children: vec![AccessStructure::Member; num_members as usize] });
+    }
+    *position += 1;
+
+    let mut children = vec![parse_node(tokens, position, total_members, depth + 1)?];
+    loop {
+        match tokens.get(*position).map(String::as_str) {
+            Some(",") => {
+                *position += 1;
+                children.push(parse_node(tokens, position, total_members, depth + 1)?);
+            }
+            Some("}") => {
+                *position += 1;
+                break;
+            }
+            _ => bail!("Expected ',' or '}}' in the access structure"),
+        }
+    }
+
+    Ok(AccessStructure::Threshold { threshold, children })
+}
+
+fn parse_number(tokens: &[String], position: &mut usize) -> Result<u64> {
+    let number = match tokens.get(*position).map(|token| token.parse::<u64>()) {
+        Some(Ok(number)) => number,
+        _ => bail!("Expected a number in the access structure"),
+    };
+    *position += 1;
+
+    Ok(number)
+}
+
+// A signer's share of a hierarchical access structure
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchicalShare {
+    // The identifier of the participant -- its position among all members of the access structure
+    pub participant_index: Identifier,
+    // The position of the participant at each level of the access structure
+    pub path: Vec<u64>,
+    // The participant's secret key share
+    pub secret_key: SignerSecretKey,
+    // The participant's public key corresponding to their secret key share
+    pub public_key: SignerPublicKey,
+    // The group public key used to verify the final threshold signature
+    pub group_public_key: GroupPublicKey,
+}
+
+// The access structure, signer public keys and the group public key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchicalPublicKeys {
+    // The access structure the shares were generated for
+    pub access_structure: AccessStructure,
+    // The map of all participant public keys.
+    pub public_keys: HashMap<Identifier, SignerPublicKey>,
+    // The group public key used to verify the final threshold signature
+    pub group_public_key: GroupPublicKey,
+}
+
+/// Generates hierarchical participant keys using a trusted authority/dealer
+///
+/// The secret is Shamir-split at the top level of the access structure, and every top-level
+/// share is itself Shamir-split among the children of its node, recursively down to the members.
+/// For a node with threshold t and secret value v, child k receives f(k) where f is a random
+/// polynomial of degree t - 1 with f(0) = v.
+pub fn hierarchical_keygen<R: Rng>(
+    access_structure: &AccessStructure,
+    secret: &Scalar<TestnetV0>,
+    rng: &mut R,
+) -> Result<(Vec<HierarchicalShare>, HierarchicalPublicKeys)> {
+    access_structure.validate()?;
+
+    let group_public_key = GroupPublicKey(Network::g_scalar_multiply(secret));
+
+    let mut shares = Vec::with_capacity(access_structure.num_members() as usize);
+    split_node(access_structure, *secret, &mut Vec::new(), &group_public_key, &mut shares, rng)?;
+
+    let public_keys = shares.iter().map(|share| (share.participant_index, share.public_key.clone())).collect();
+    let public_keys = HierarchicalPublicKeys { access_structure: access_structure.clone(), public_keys, group_public_key };
+
+    Ok((shares, public_keys))
+}
+
+fn split_node<R: Rng>(
+    node: &AccessStructure,
+    value: Scalar<TestnetV0>,
+    path: &mut Vec<u64>,
+    group_public_key: &GroupPublicKey,
+    shares: &mut Vec<HierarchicalShare>,
+    rng: &mut R,
+) -> Result<()> {
+    match node {
+        AccessStructure::Member => shares.push(HierarchicalShare {
+            participant_index: Identifier::from_u64(shares.len() as u64 + 1)?,
+            path: path.clone(),
+            secret_key: SignerSecretKey(value),
+            public_key: SignerPublicKey(Network::g_scalar_multiply(&value)),
+            group_public_key: group_public_key.clone(),
+        }),
+        AccessStructure::Threshold { threshold, children } => {
+            let mut coefficients = vec![value];
+            for _ in 1..*threshold {
+                coefficients.push(Scalar::<TestnetV0>::rand(rng));
+            }
+
+            for (position, child) in children.iter().enumerate() {
+                let position = position as u64 + 1;
+
+                // Evaluate the node's polynomial at the child's position using Horner's Method
+                let position_scalar = Identifier::from_u64(position)?.to_scalar();
+                let mut result = Scalar::<TestnetV0>::zero();
+                for (i, coeff) in coefficients.iter().rev().enumerate() {
+                    result = result + coeff;
+
+                    if i != coefficients.len() - 1 {
+                        result = result * position_scalar;
+                    }
+                }
+
+                path.push(position);
+                split_node(child, result, path, group_public_key, shares, rng)?;
+                path.pop();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl HierarchicalPublicKeys {
+    // Check that the signing participants satisfy the access structure, ie that Sum coeff_j * Y_j = group public key
+    pub fn is_satisfied_by(&self, participant_indices: &[Identifier]) -> Result<bool> {
+        let coefficients = match self.access_structure.lagrange_coefficients(participant_indices)? {
+            Some(coefficients) => coefficients,
+            None => return Ok(false),
+        };
+
+        let mut result = Group::<TestnetV0>::zero();
+        for (participant_index, coefficient) in coefficients {
+            match self.public_keys.get(&participant_index) {
+                Some(public_key) => result = result + public_key.0 * coefficient,
+                None => bail!("Participant {participant_index} is not part of the group"),
+            }
+        }
+
+        Ok(result == self.group_public_key.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{frost::*, generate_message::SigningMessage, preprocess::*};
+
+    use snarkvm_console_account::{compute_key::*, private_key::*, Address};
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_parser() {
+        let structure = AccessStructure::from_str("2 of {2 of 3, 1 of 2, 1 of 1}").unwrap();
+        assert_eq!(structure.num_members(), 6);
+        assert_eq!(structure.to_string(), "2 of {2 of 3, 1 of 2, 1 of 1}");
+        assert_eq!(AccessStructure::from_str(&structure.to_string()).unwrap(), structure);
+
+        for policy in ["", "2", "2 of", "0 of 3", "4 of 3", "2 of {1 of 1}", "2 of {1 of 1, 1 of 1", "1 of 1 1", "1 of -1", "1 of {}"] {
+            assert!(AccessStructure::from_str(policy).is_err(), "'{policy}' was accepted");
+        }
+    }
+
+    #[test]
+    fn test_parser_limits() {
+        // MAX_MEMBERS in a single node and across nodes
+        assert!(AccessStructure::from_str(&format!("1 of {}", MAX_MEMBERS + 1)).is_err());
+        assert!(AccessStructure::from_str(&format!("1 of {{1 of {MAX_MEMBERS}, 1 of 1}}")).is_err());
+        assert!(AccessStructure::from_str("1 of 18446744073709551615").is_err());
+
+        // MAX_DEPTH levels of nesting
+        let nested = |depth: usize| format!("{}1 of 1{}", "1 of {".repeat(depth), "}".repeat(depth));
+        assert!(AccessStructure::from_str(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(AccessStructure::from_str(&nested(MAX_DEPTH)).is_err());
+    }
+
+    #[test]
+    fn test_hierarchical_signature_verifies() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let compute_key = ComputeKey::try_from(&private_key).unwrap();
+        let address = Address::try_from(&compute_key).unwrap();
+
+        // Any 2 of the three teams, where the first team needs 2 of its 3 members
+        let structure = AccessStructure::from_str("2 of {2 of 3, 1 of 2, 1 of 1}").unwrap();
+        let (shares, public_keys) = hierarchical_keygen(&structure, &private_key.sk_sig(), rng).unwrap();
+        assert_eq!(public_keys.group_public_key.0, compute_key.pk_sig());
+        let member = |index: u64| shares.iter().find(|share| share.participant_index == Identifier::from_u64(index).unwrap()).unwrap();
+
+        let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+        for signers in [vec![1, 2, 6], vec![3, 4, 6], vec![1, 3, 5]] {
+            let signers: Vec<&HierarchicalShare> = signers.into_iter().map(member).collect();
+            let indices: Vec<Identifier> = signers.iter().map(|share| share.participant_index).collect();
+            assert!(public_keys.is_satisfied_by(&indices).unwrap());
+
+            let nonces: Vec<SigningNonce> = signers.iter().map(|_| SigningNonce::new(rng)).collect();
+            let commitments: Vec<SigningCommitment> =
+                signers.iter().zip(&nonces).map(|(share, nonce)| SigningCommitment::from(share.participant_index, nonce)).collect();
+            let partial_signatures: Vec<PartialThresholdSignature> = signers
+                .iter()
+                .zip(&nonces)
+                .map(|(share, nonce)| {
+                    PartialThresholdSignature::new_hierarchical_partial_sig(
+                        share,
+                        nonce,
+                        commitments.clone(),
+                        &structure,
+                        message.to_fields().unwrap(),
+                        compute_key.pr_sig(),
+                    )
+                    .unwrap()
+                })
+                .collect();
+
+            let package = SigningPackage::new(commitments, message.clone());
+            let challenge = package.challenge(&public_keys.group_public_key, compute_key.pr_sig()).unwrap();
+            let signature = aggregate_signature(challenge, &partial_signatures, compute_key);
+            assert!(signature.verify(&address, &message.to_fields().unwrap()));
+        }
+
+        // Two members of the first team alone satisfy only one team
+        let indices = [1, 2].map(|index| Identifier::from_u64(index).unwrap());
+        assert!(!public_keys.is_satisfied_by(&indices).unwrap());
+        let nonce = SigningNonce::new(rng);
+        let commitments = indices.iter().map(|index| SigningCommitment::from(*index, &nonce)).collect();
+        let share = member(1);
+        let partial_signature = PartialThresholdSignature::new_hierarchical_partial_sig(
+            share,
+            &nonce,
+            commitments,
+            &structure,
+            message.to_fields().unwrap(),
+            compute_key.pr_sig(),
+        );
+        assert!(partial_signature.is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use snarkvm_console_account::Address;
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Field, Scalar};
+use snarkvm_console_types_scalar::{ensure, Error, FromBits, FromField, Result, SizeInDataBits, ToBits, ToField, Zero};
+
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// Domain separator for identifiers derived from labels
+const LABEL_DOMAIN: &str = "AleoFrostIdentifierLabel";
+/// Domain separator for identifiers derived from Aleo addresses
+const ADDRESS_DOMAIN: &str = "AleoFrostIdentifierAddress";
+
+/// A participant identifier -- the nonzero scalar at which the secret polynomial is evaluated
+///
+/// Identifiers are either built from integers (1, 2, ..., n for a trusted dealer) or derived
+/// by hashing a label or an Aleo address, so committees are not limited in size.
+///
+/// Deserialization goes through `Identifier::new`, so a zero identifier is rejected when decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Scalar<TestnetV0>", into = "Scalar<TestnetV0>")]
+pub struct Identifier(Scalar<TestnetV0>);
+
+impl Identifier {
+    // Construct an identifier from a scalar, rejecting zero (f(0) is the group secret)
+    pub fn new(scalar: Scalar<TestnetV0>) -> Result<Self> {
+        ensure!(!scalar.is_zero(), "A participant identifier must not be zero");
+        Ok(Self(scalar))
+    }
+
+    // Construct an identifier from an integer index
+    pub fn from_u64(index: u64) -> Result<Self> {
+        Self::new(Scalar::<TestnetV0>::from_field(&Field::from_u64(index))?)
+    }
+
+    /// Derive an identifier by hashing a label
+    ///
+    /// id = H(domain, label) where the label's UTF-8 bytes are packed into fields
+    pub fn derive(label: &str) -> Result<Self> {
+        let mut preimage = vec![Field::<TestnetV0>::new_domain_separator(LABEL_DOMAIN), Field::from_u64(label.len() as u64)];
+        for chunk in label.as_bytes().to_bits_le().chunks(Field::<TestnetV0>::size_in_data_bits()) {
+            preimage.push(Field::from_bits_le(chunk)?);
+        }
+
+        Self::new(TestnetV0::hash_to_scalar_psd4(&preimage)?)
+    }
+
+    /// Derive an identifier by hashing an Aleo address
+    ///
+    /// id = H(domain, address)
+    pub fn from_address(address: &Address<TestnetV0>) -> Result<Self> {
+        let preimage = [Field::<TestnetV0>::new_domain_separator(ADDRESS_DOMAIN), address.to_x_coordinate()];
+
+        Self::new(TestnetV0::hash_to_scalar_psd2(&preimage)?)
+    }
+
+    // The identifier as a scalar
+    pub fn to_scalar(&self) -> Scalar<TestnetV0> {
+        self.0
+    }
+
+    // The identifier as a field element, used in hash preimages
+    pub fn to_field(&self) -> Result<Field<TestnetV0>> {
+        self.0.to_field()
+    }
+}
+
+impl TryFrom<Scalar<TestnetV0>> for Identifier {
+    type Error = Error;
+
+    fn try_from(scalar: Scalar<TestnetV0>) -> Result<Self> {
+        Self::new(scalar)
+    }
+}
+
+impl From<Identifier> for Scalar<TestnetV0> {
+    fn from(identifier: Identifier) -> Self {
+        identifier.0
+    }
+}
+
+impl TryFrom<u64> for Identifier {
+    type Error = Error;
+
+    fn try_from(index: u64) -> Result<Self> {
+        Self::from_u64(index)
+    }
+}
+
+// Identifiers are ordered by their scalar value so every party sorts a signing set the same way
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.to_bits_le().iter().rev().cmp(other.0.to_bits_le().iter().rev())
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = Error;
+
+    fn from_str(identifier: &str) -> Result<Self> {
+        Self::new(Scalar::<TestnetV0>::from_str(identifier)?)
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{keys::*, utils::*};
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_zero_identifier_is_rejected() {
+        assert!(Identifier::new(Scalar::<TestnetV0>::zero()).is_err());
+        assert!(Identifier::from_u64(0).is_err());
+        assert!(Identifier::try_from(0u64).is_err());
+        assert!(Identifier::from_str("0scalar").is_err());
+
+        // Decoding goes through Identifier::new as well
+        let zero = serde_json::to_string(&Scalar::<TestnetV0>::zero()).unwrap();
+        assert!(serde_json::from_str::<Identifier>(&zero).is_err());
+
+        let identifier = Identifier::from_u64(7).unwrap();
+        let json = serde_json::to_string(&identifier).unwrap();
+        assert_eq!(serde_json::from_str::<Identifier>(&json).unwrap(), identifier);
+        assert_eq!(Identifier::from_str(&identifier.to_string()).unwrap(), identifier);
+    }
+
+    #[test]
+    fn test_derived_identifiers() {
+        let rng = &mut TestRng::default();
+        assert_eq!(Identifier::derive("alice").unwrap(), Identifier::derive("alice").unwrap());
+        assert_ne!(Identifier::derive("alice").unwrap(), Identifier::derive("bob").unwrap());
+        assert_ne!(Identifier::derive("").unwrap(), Identifier::derive("\0").unwrap());
+
+        let address = Address::try_from(&PrivateKey::<TestnetV0>::new(rng).unwrap()).unwrap();
+        let other = Address::try_from(&PrivateKey::<TestnetV0>::new(rng).unwrap()).unwrap();
+        assert_eq!(Identifier::from_address(&address).unwrap(), Identifier::from_address(&address).unwrap());
+        assert_ne!(Identifier::from_address(&address).unwrap(), Identifier::from_address(&other).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_identifiers_are_rejected() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (a, b) = (Identifier::derive("alice").unwrap(), Identifier::derive("bob").unwrap());
+
+        assert!(trusted_keygen_with_identifiers(&[a, b], 2, &private_key.sk_sig(), rng).is_ok());
+        assert!(trusted_keygen_with_identifiers(&[a, b, a], 2, &private_key.sk_sig(), rng).is_err());
+        assert!(trusted_keygen_weighted(&[(a, 1), (b, 1), (a, 1)], 2, &private_key.sk_sig(), rng).is_err());
+
+        assert!(calculate_lagrange_coefficients(a, &[a, b], None).is_ok());
+        assert!(calculate_lagrange_coefficients(a, &[a, b, b], None).is_err());
+        assert!(calculate_lagrange_coefficients(b, &[a, b, b], None).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, path::Path};
+
+use snarkvm_console_account::{private_key::*, signature::*, Address};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{envelope::*, identifier::Identifier};
+
+/// The long-term Aleo identity of every party of the group
+///
+/// Identity keys only authenticate round messages, they are unrelated to the threshold account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    // The address of the coordinator's identity key
+    pub coordinator: Address<TestnetV0>,
+    // The address of each participant's identity key
+    pub participants: HashMap<Identifier, Address<TestnetV0>>,
+}
+
+impl GroupConfig {
+    // Read a group config from a JSON file on disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    // Write the group config to disk as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // The registered identity address of a party
+    pub fn identity(&self, party: &Party) -> Result<Address<TestnetV0>> {
+        match party {
+            Party::Coordinator => Ok(self.coordinator),
+            Party::Participant(identifier) => match self.participants.get(identifier) {
+                Some(address) => Ok(*address),
+                None => bail!("Participant {identifier} is not registered in the group config"),
+            },
+            Party::Broadcast => bail!("Broadcast is not a party with an identity"),
+        }
+    }
+}
+
+/// An envelope signed by its sender's identity key
+///
+/// The signature is over the exact encoded bytes with `sign_bytes`, which are kept as sent so
+/// re-encoding on the receiver's side cannot change what is verified.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedEnvelope {
+    // The JSON encoding of the envelope
+    pub envelope: String,
+    // The sender's signature over the encoding
+    pub signature: Signature<TestnetV0>,
+}
+
+impl SignedEnvelope {
+    // Sign an envelope with the sender's identity key
+    pub fn sign<R: Rng>(envelope: &Envelope, identity_key: &PrivateKey<TestnetV0>, rng: &mut R) -> Result<Self> {
+        let encoded = String::from_utf8(envelope.encode()?)?;
+        let signature = identity_key.sign_bytes(encoded.as_bytes(), rng)?;
+
+        Ok(Self { envelope: encoded, signature })
+    }
+
+    /// Decode the envelope after checking it is signed by its sender's registered identity
+    ///
+    /// Envelopes from parties missing from the group config, or signed by any other key than
+    /// the one registered for the sender, are rejected.
+    pub fn open(&self, config: &GroupConfig) -> Result<Envelope> {
+        let envelope = Envelope::decode(self.envelope.as_bytes())?;
+
+        let identity = config.identity(&envelope.sender)?;
+        ensure!(
+            self.signature.to_address() == identity,
+            "The {} from {} is signed by {}, not by its registered identity {identity}",
+            envelope.payload.name(), envelope.sender, self.signature.to_address()
+        );
+        ensure!(
+            self.signature.verify_bytes(&identity, self.envelope.as_bytes()),
+            "Invalid signature on the {} from {}", envelope.payload.name(), envelope.sender
+        );
+
+        Ok(envelope)
+    }
+
+    // Encode the signed envelope as JSON
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    // Decode a signed envelope, rejecting unknown fields; the envelope is checked by `open`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preprocess::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    // The identity keys of the coordinator and two participants, with their group config
+    fn group(rng: &mut TestRng) -> (Vec<PrivateKey<TestnetV0>>, GroupConfig) {
+        let keys: Vec<PrivateKey<TestnetV0>> = (0..3).map(|_| PrivateKey::new(rng).unwrap()).collect();
+        let config = GroupConfig {
+            coordinator: Address::try_from(&keys[0]).unwrap(),
+            participants: HashMap::from([
+                (Identifier::from_u64(1).unwrap(), Address::try_from(&keys[1]).unwrap()),
+                (Identifier::from_u64(2).unwrap(), Address::try_from(&keys[2]).unwrap()),
+            ]),
+        };
+
+        (keys, config)
+    }
+
+    // A commitment announcement from participant `sender` to the coordinator
+    fn announcement(sender: u64, rng: &mut TestRng) -> Envelope {
+        let sender = Identifier::from_u64(sender).unwrap();
+        let commitment = SigningCommitment::from(sender, &SigningNonce::new(rng));
+
+        Envelope::new("session-1", Party::Participant(sender), Party::Coordinator, 0, Payload::CommitmentAnnouncement(vec![commitment])).unwrap()
+    }
+
+    #[test]
+    fn test_open_checks_the_registered_identity() {
+        let rng = &mut TestRng::default();
+        let (keys, config) = group(rng);
+        let envelope = announcement(1, rng);
+
+        let signed = SignedEnvelope::sign(&envelope, &keys[1], rng).unwrap();
+        assert_eq!(signed.open(&config).unwrap(), envelope);
+        assert_eq!(SignedEnvelope::decode(&signed.encode().unwrap()).unwrap().open(&config).unwrap(), envelope);
+
+        // Signed by the coordinator, another participant or an unknown key
+        assert!(SignedEnvelope::sign(&envelope, &keys[0], rng).unwrap().open(&config).is_err());
+        assert!(SignedEnvelope::sign(&envelope, &keys[2], rng).unwrap().open(&config).is_err());
+        assert!(SignedEnvelope::sign(&envelope, &PrivateKey::new(rng).unwrap(), rng).unwrap().open(&config).is_err());
+
+        // From a participant missing from the group config, even when signed by a registered key
+        let unregistered = announcement(3, rng);
+        for key in &keys {
+            assert!(SignedEnvelope::sign(&unregistered, key, rng).unwrap().open(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_open_rejects_edited_envelopes() {
+        let rng = &mut TestRng::default();
+        let (keys, config) = group(rng);
+        let signed = SignedEnvelope::sign(&announcement(1, rng), &keys[1], rng).unwrap();
+
+        // The signature covers the exact bytes, including whitespace
+        let edited = SignedEnvelope { envelope: signed.envelope.replace("\"sequence\":0", "\"sequence\":1"), ..signed.clone() };
+        assert_ne!(edited.envelope, signed.envelope);
+        assert!(edited.open(&config).is_err());
+        let edited = SignedEnvelope { envelope: format!("{} ", signed.envelope), ..signed.clone() };
+        assert!(edited.open(&config).is_err());
+
+        // Another envelope under the signature of this one
+        let other = SignedEnvelope::sign(&announcement(1, rng), &keys[1], rng).unwrap();
+        assert!(SignedEnvelope { envelope: other.envelope, ..signed.clone() }.open(&config).is_err());
+
+        let mut json = serde_json::to_value(&signed).unwrap();
+        json["sender"] = "coordinator".into();
+        assert!(SignedEnvelope::decode(&serde_json::to_vec(&json).unwrap()).is_err());
+    }
+}
@@ -1,39 +1,54 @@
 use snarkvm_console_network::{Network, TestnetV0};
-use snarkvm_console_types::{Group, Scalar, U8, U64};
-use snarkvm_console_types_scalar::{Uniform, FromField, ToField, Zero};
+use snarkvm_console_types::{Group, Scalar};
+use snarkvm_console_account::PrivateKey;
+use snarkvm_console_types_scalar::{bail, ensure, Field, Inverse, One, Result, Uniform, Zero};
 
-use rand::{Rng, Error};
-use std::collections::HashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
 
-use crate::utils::*;
+use crate::{identifier::Identifier, utils::*};
 
 
 // The public key used to verify a threshold signature made by a group of signers
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GroupPublicKey (pub Group<TestnetV0>);
 
 // The signer's public key -- not really worth much
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignerPublicKey (pub Group<TestnetV0>);
 
 // The signer's secret key -- sk_sig partial
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignerSecretKey (pub Scalar<TestnetV0>);
 
 // The list of signer public keys and the group public key
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PublicKeys {
     // The map of all participant public keys.
-    pub public_keys: HashMap<u64, SignerPublicKey>,
+    pub public_keys: HashMap<Identifier, SignerPublicKey>,
     // The group public key used to verify the final threshold signature
     pub group_public_key: GroupPublicKey,
+    // The share indices owned by each participant -- its weight is the number of indices
+    pub participant_shares: HashMap<Identifier, Vec<Identifier>>,
+}
+
+impl PublicKeys {
+    // The weight of a participant, ie the number of share indices it owns
+    pub fn weight(&self, participant_id: Identifier) -> usize {
+        self.participant_shares.get(&participant_id).map_or(0, |indices| indices.len())
+    }
 }
 
 // A signer's share that includes its secret key and all publicly known keys/commitments
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SignerShare {
-    // The index of the participant.
-    pub participant_index: u64,
+    // The identifier of the share, ie the point at which the secret polynomial is evaluated.
+    pub participant_index: Identifier,
+    // The participant owning this share -- equal to participant_index unless the participant is weighted
+    pub participant_id: Identifier,
     // The participant's secret key share
     pub secret_key: SignerSecretKey,
     // The participant's public key corresponding to their secret key share
@@ -41,7 +56,10 @@ pub struct SignerShare {
     // The group public key used to verify the final threshold signature
     pub group_public_key: GroupPublicKey,
     // The public commitments to the coefficients (generated by each participant)
-    pub commitment: Vec<Group<TestnetV0>>
+    pub commitment: Vec<Group<TestnetV0>>,
+    // The participant's share of the account seed, for emergency recovery of the full private key
+    #[serde(default)]
+    pub recovery_share: Option<Field<TestnetV0>>,
 }
 
 impl SignerShare {
@@ -49,19 +67,100 @@ impl SignerShare {
     pub fn is_valid(&self) -> bool {
         let expected_result = Network::g_scalar_multiply(&self.secret_key.0);
 
-        let index_scalar = Scalar::<TestnetV0>::from_field(&U64::new(self.participant_index).to_field().unwrap()).unwrap();
-        let mut result = Group::<TestnetV0>::zero();
-        for (i, c) in self.commitment.iter().rev().enumerate() {
-            result = result + c;
+        expected_result == evaluate_commitment(&self.commitment, self.participant_index)
+    }
+    
+}
 
-            if i != self.commitment.len() - 1 {
-                result = result * index_scalar;
-            }
+// The public keys of a group together with the commitment they were derived from
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublicKeyPackage {
+    // The map of all participant public keys.
+    pub public_keys: HashMap<Identifier, SignerPublicKey>,
+    // The group public key used to verify the final threshold signature
+    pub group_public_key: GroupPublicKey,
+    // The share indices owned by each participant -- its weight is the number of indices
+    pub participant_shares: HashMap<Identifier, Vec<Identifier>>,
+    // The public commitments to the coefficients of the secret polynomial
+    pub commitment: Vec<Group<TestnetV0>>,
+}
+
+impl PublicKeyPackage {
+    pub fn new(
+        public_keys: PublicKeys,
+        commitment: Vec<Group<TestnetV0>>,
+    ) -> Self {
+        Self {
+            public_keys: public_keys.public_keys,
+            group_public_key: public_keys.group_public_key,
+            participant_shares: public_keys.participant_shares,
+            commitment,
+        }
+    }
+
+    /// Verify that every public key in the package is consistent with the commitment
+    ///
+    /// - The constant term of the commitment must equal the group public key
+    /// - Each SignerPublicKey must equal the commitment evaluated at the participant's index
+    ///
+    /// A tampered package is rejected here, before any of its keys are used for signing.
+    pub fn verify(&self) -> Result<()> {
+        ensure!(!self.commitment.is_empty(), "The public key package has an empty commitment");
+        ensure!(
+            self.commitment[0] == self.group_public_key.0,
+            "The commitment does not match the group public key"
+        );
+        ensure!(
+            self.public_keys.len() >= self.commitment.len(),
+            "The public key package has fewer participants than its threshold"
+        );
+
+        for (participant_index, public_key) in &self.public_keys {
+            // f(0) is the group secret, so a zero index would publish g^secret as a share
+            ensure!(!participant_index.to_scalar().is_zero(), "The public key package contains a zero participant index");
+            ensure!(
+                public_key.0 == evaluate_commitment(&self.commitment, *participant_index),
+                "The public key of participant {participant_index} does not match the commitment"
+            );
         }
 
-        expected_result == result
+        // Every share index must be owned by exactly one participant
+        let mut owned_indices: Vec<Identifier> = self.participant_shares.values().flatten().copied().collect();
+        owned_indices.sort_unstable();
+        owned_indices.dedup();
+        ensure!(
+            owned_indices.len() == self.public_keys.len()
+                && self.participant_shares.values().map(|indices| indices.len()).sum::<usize>() == owned_indices.len()
+                && owned_indices.iter().all(|index| self.public_keys.contains_key(index)),
+            "The participant weights do not match the public keys of the package"
+        );
+
+        Ok(())
+    }
+
+    // The participant public keys and the group public key of a verified package
+    pub fn public_keys(&self) -> Result<PublicKeys> {
+        self.verify()?;
+
+        Ok(PublicKeys {
+            public_keys: self.public_keys.clone(),
+            group_public_key: self.group_public_key.clone(),
+            participant_shares: self.participant_shares.clone(),
+        })
+    }
+
+    // Read a public key package from a JSON file on disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    // Write the public key package to disk as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
     }
-    
 }
 
 /// Generates the participant keys using a trusted authority/dealer (can also be done with DKG)
@@ -75,19 +174,66 @@ impl SignerShare {
 /// The 'secret' is input as the sk_sig of a previously generated account
 /// g^sk_sig = GroupPublicKey = pk_sig
 pub fn trusted_keygen<R: Rng> (
-    num_participants: u8,
-    threshold: u8,
+    num_participants: u16,
+    threshold: u16,
     secret: &Scalar<TestnetV0>,
     rng: &mut R,
-) -> (Vec<SignerShare>, PublicKeys) {
-    if num_participants < 1 {
-        panic!("the number of participants must be greater than 0.")
-    }
+) -> Result<(Vec<SignerShare>, PublicKeys)> {
+    let identifiers = (1..num_participants as u64 + 1).map(Identifier::from_u64).collect::<Result<Vec<_>>>()?;
 
-    if threshold < 1 || threshold > num_participants {
-        panic!("the threshold must be between 1 and num_participants");
+    trusted_keygen_with_identifiers(&identifiers, threshold, secret, rng)
+}
+
+/// Generates the participant keys for the given participant identifiers using a trusted authority/dealer
+///
+/// The identifiers can be integers or derived from labels or Aleo addresses with `Identifier::derive`
+/// and `Identifier::from_address`. Participant i's secret share is f(id_i).
+pub fn trusted_keygen_with_identifiers<R: Rng> (
+    identifiers: &[Identifier],
+    threshold: u16,
+    secret: &Scalar<TestnetV0>,
+    rng: &mut R,
+) -> Result<(Vec<SignerShare>, PublicKeys)> {
+    let owners = identifiers.iter().map(|identifier| (*identifier, *identifier)).collect();
+
+    dealer_keygen(owners, threshold as u64, secret, rng)
+}
+
+/// Generates weighted participant keys using a trusted authority/dealer
+///
+/// Each participant receives as many consecutive share indices (1, 2, ...) of the same secret
+/// polynomial f as its weight, so the threshold is met by total weight rather than headcount.
+pub fn trusted_keygen_weighted<R: Rng> (
+    weights: &[(Identifier, u16)],
+    threshold: u64,
+    secret: &Scalar<TestnetV0>,
+    rng: &mut R,
+) -> Result<(Vec<SignerShare>, PublicKeys)> {
+    ensure!(weights.iter().all(|(_, weight)| *weight > 0), "Each participant's weight must be greater than 0");
+
+    let mut owners = Vec::new();
+    for (participant_id, weight) in weights {
+        ensure!(owners.iter().all(|(owner, _)| owner != participant_id), "Participant {participant_id} is listed more than once");
+        for _ in 0..*weight {
+            owners.push((*participant_id, Identifier::from_u64(owners.len() as u64 + 1)?));
+        }
     }
 
+    dealer_keygen(owners, threshold, secret, rng)
+}
+
+// Deal one share of the secret polynomial per (participant identifier, share index) pair
+fn dealer_keygen<R: Rng> (
+    owners: Vec<(Identifier, Identifier)>,
+    threshold: u64,
+    secret: &Scalar<TestnetV0>,
+    rng: &mut R,
+) -> Result<(Vec<SignerShare>, PublicKeys)> {
+    ensure!(!owners.is_empty(), "The number of participants must be greater than 0");
+
+    let total_weight = owners.len() as u64;
+    ensure!(threshold >= 1 && threshold <= total_weight, "The threshold must be between 1 and {total_weight}, found {threshold}");
+
     let mut coefficients: Vec<Scalar<TestnetV0>> = Vec::with_capacity(threshold as usize);
     let mut share_commitment: Vec<Group<TestnetV0>> = Vec::with_capacity(threshold as usize);
 
@@ -108,13 +254,16 @@ pub fn trusted_keygen<R: Rng> (
     let group_public_key = Network::g_scalar_multiply(secret);
 
     // FROST Keygen Round 2: Generate the secret shares for each participant using a centralized authority
-    let mut shares = Vec::with_capacity(num_participants as usize);
-    let mut participant_public_keys: HashMap<u64, SignerPublicKey> = HashMap::with_capacity(num_participants as usize);
+    let mut shares = Vec::with_capacity(owners.len());
+    let mut participant_public_keys: HashMap<Identifier, SignerPublicKey> = HashMap::with_capacity(owners.len());
+    let mut participant_shares: HashMap<Identifier, Vec<Identifier>> = HashMap::new();
 
     // Evaluate the polynomial f at point 'index' to generate secret shares.
     // Using Horner's Method
-    for index in 1..num_participants +1 {
-        let index_scalar = Scalar::<TestnetV0>::from_field(&U8::new(index).to_field().unwrap()).unwrap();
+    for (participant_id, index) in owners {
+        ensure!(!participant_public_keys.contains_key(&index), "The participant identifiers must be distinct, {index} is repeated");
+
+        let index_scalar = index.to_scalar();
         let mut result = Scalar::<TestnetV0>::zero();
         for (i, coeff) in coefficients.iter().rev().enumerate() {
             result = result + coeff;
@@ -127,36 +276,261 @@ pub fn trusted_keygen<R: Rng> (
         let public_key= Network::g_scalar_multiply(&result);
 
         let secret_share = SignerShare {
-            participant_index: index as u64,
+            participant_index: index,
+            participant_id,
             secret_key: SignerSecretKey(result),
             public_key: SignerPublicKey(public_key.clone()),
             group_public_key: GroupPublicKey(group_public_key.clone()),
             commitment: share_commitment.clone(),
+            recovery_share: None,
         };
 
-        participant_public_keys.insert(index as u64, SignerPublicKey(public_key));
+        participant_public_keys.insert(index, SignerPublicKey(public_key));
+        participant_shares.entry(participant_id).or_default().push(index);
         shares.push(secret_share);
     }
 
-    let public_keys = PublicKeys { public_keys: participant_public_keys, group_public_key: GroupPublicKey(group_public_key) };
+    let public_keys = PublicKeys {
+        public_keys: participant_public_keys,
+        group_public_key: GroupPublicKey(group_public_key),
+        participant_shares,
+    };
+
+    Ok((shares, public_keys))
+}
+
+/// Splits the account seed among the key shares, so the full private key can be recovered
+///
+/// The seed is Shamir-shared over the base field with the same threshold and share indices as
+/// sk_sig: share i receives g(id_i) where g is a random polynomial with g(0) = seed. No single
+/// share holder learns the seed, and `reconstruct_private_key` rebuilds it from t shares.
+pub fn add_recovery_shares<R: Rng>(
+    shares: &mut [SignerShare],
+    private_key: &PrivateKey<TestnetV0>,
+    rng: &mut R,
+) -> Result<()> {
+    let first = match shares.first() {
+        Some(first) => first.clone(),
+        None => bail!("At least one key share is required to split the seed"),
+    };
+    ensure!(
+        Network::g_scalar_multiply(&private_key.sk_sig()) == first.group_public_key.0,
+        "The private key does not belong to the group of the key shares"
+    );
+
+    let mut coefficients = vec![private_key.seed()];
+    for _ in 1..first.commitment.len() {
+        coefficients.push(Field::<TestnetV0>::rand(rng));
+    }
+
+    for share in shares.iter_mut() {
+        ensure!(share.group_public_key == first.group_public_key, "Key share {} belongs to a different group", share.participant_index);
 
-    (shares, public_keys)
+        // Evaluate the seed polynomial at the share's index using Horner's Method
+        let index = share.participant_index.to_field()?;
+        let mut result = Field::<TestnetV0>::zero();
+        for (i, coeff) in coefficients.iter().rev().enumerate() {
+            result = result + coeff;
 
+            if i != coefficients.len() - 1 {
+                result = result * index;
+            }
+        }
+        share.recovery_share = Some(result);
+    }
 
+    Ok(())
 }
 
+/// Reconstructs the group secret (sk_sig) from the key shares of at least `threshold` participants
+///
+/// The threshold and commitment are taken from the group's verified `PublicKeyPackage`, not from
+/// the shares. The shares must have distinct indices, match the package's public keys and be
+/// consistent with its commitment, and the reconstructed secret is only returned if
+/// g^secret == group_public_key.
 pub fn reconstruct_secret(
-    participants: &[SignerShare]
-) -> Result<SignerSecretKey, Error> {
-    let indexes: Vec<u64> = participants.iter().map(|p| p.participant_index).collect();
+    public_key_package: &PublicKeyPackage,
+    participants: &[SignerShare],
+) -> Result<SignerSecretKey> {
+    public_key_package.verify()?;
+    let threshold = public_key_package.commitment.len();
+
+    let mut indexes: Vec<Identifier> = Vec::with_capacity(participants.len());
+    for participant in participants {
+        ensure!(!participant.participant_index.to_scalar().is_zero(), "A key share has a zero participant index");
+        ensure!(!indexes.contains(&participant.participant_index), "Duplicate key share for participant {}", participant.participant_index);
+        ensure!(
+            participant.group_public_key == public_key_package.group_public_key && participant.commitment == public_key_package.commitment,
+            "Key share for participant {} belongs to a different group", participant.participant_index
+        );
+        ensure!(
+            public_key_package.public_keys.get(&participant.participant_index) == Some(&participant.public_key),
+            "Key share for participant {} does not match the public key package", participant.participant_index
+        );
+        ensure!(participant.is_valid(), "Key share for participant {} does not match the public commitment", participant.participant_index);
+        indexes.push(participant.participant_index);
+    }
+
+    ensure!(indexes.len() >= threshold, "Reconstruction requires {threshold} key shares, found {}", indexes.len());
 
     let mut reconstructed_secret = Scalar::<TestnetV0>::zero();
 
     for participant in participants {
-        let coeff = calculate_lagrange_coefficients(participant.participant_index, &indexes).unwrap();
+        let coeff = calculate_lagrange_coefficients(participant.participant_index, &indexes, None)?;
 
         reconstructed_secret = reconstructed_secret + participant.secret_key.0 * coeff;
     }
 
+    ensure!(
+        Network::g_scalar_multiply(&reconstructed_secret) == public_key_package.group_public_key.0,
+        "The reconstructed secret does not match the group public key"
+    );
+
     Ok(SignerSecretKey(reconstructed_secret))
-}
\ No newline at end of file
+}
+
+/// Reconstructs the full account private key for emergency recovery
+///
+/// Aleo derives sk_sig and r_sig from the account seed, so the seed is rebuilt from the
+/// recovery shares of the same t shares (see `add_recovery_shares`). The private key is only
+/// returned if its sk_sig equals the secret reconstructed from the key shares.
+pub fn reconstruct_private_key(
+    public_key_package: &PublicKeyPackage,
+    participants: &[SignerShare],
+) -> Result<PrivateKey<TestnetV0>> {
+    let reconstructed_secret = reconstruct_secret(public_key_package, participants)?;
+
+    let mut indexes = Vec::with_capacity(participants.len());
+    for participant in participants {
+        indexes.push(participant.participant_index.to_field()?);
+    }
+
+    // Interpolate the seed polynomial at 0 over the base field
+    let mut seed = Field::<TestnetV0>::zero();
+    for (participant, index) in participants.iter().zip(&indexes) {
+        let recovery_share = match participant.recovery_share {
+            Some(recovery_share) => recovery_share,
+            None => bail!("Key share for participant {} has no recovery share", participant.participant_index),
+        };
+
+        let mut numerator = Field::<TestnetV0>::one();
+        let mut denominator = Field::<TestnetV0>::one();
+        for other in indexes.iter().filter(|other| *other != index) {
+            numerator = numerator * *other;
+            denominator = denominator * (*other - *index);
+        }
+        seed = seed + recovery_share * numerator * denominator.inverse()?;
+    }
+
+    let private_key = PrivateKey::<TestnetV0>::try_from(seed)?;
+    ensure!(
+        private_key.sk_sig() == reconstructed_secret.0,
+        "The recovery shares do not rebuild the seed of the group's private key"
+    );
+
+    Ok(private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    // The shares of a 2-of-3 group with recovery shares, the group's package and its private key
+    fn group(rng: &mut TestRng) -> (Vec<SignerShare>, PublicKeyPackage, PrivateKey<TestnetV0>) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (mut shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        add_recovery_shares(&mut shares, &private_key, rng).unwrap();
+        let package = PublicKeyPackage::new(public_keys, shares[0].commitment.clone());
+
+        (shares, package, private_key)
+    }
+
+    #[test]
+    fn test_reconstruction_from_threshold_shares() {
+        let rng = &mut TestRng::default();
+        let (shares, package, private_key) = group(rng);
+
+        for participants in [&shares[..2], &shares[1..], &shares[..]] {
+            assert_eq!(reconstruct_secret(&package, participants).unwrap().0, private_key.sk_sig());
+            assert_eq!(reconstruct_private_key(&package, participants).unwrap(), private_key);
+        }
+    }
+
+    #[test]
+    fn test_reconstruction_below_threshold_is_refused() {
+        let rng = &mut TestRng::default();
+        let (shares, package, _) = group(rng);
+
+        assert!(reconstruct_secret(&package, &shares[..1]).is_err());
+        assert!(reconstruct_secret(&package, &[]).is_err());
+        // The same share twice does not count as two
+        assert!(reconstruct_secret(&package, &[shares[0].clone(), shares[0].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_reconstruction_from_inconsistent_shares_is_refused() {
+        let rng = &mut TestRng::default();
+        let (shares, package, _) = group(rng);
+
+        // A secret key that does not match the share's public key
+        let mut tampered = shares[..2].to_vec();
+        tampered[0].secret_key = SignerSecretKey(tampered[0].secret_key.0 + Scalar::<TestnetV0>::one());
+        assert!(reconstruct_secret(&package, &tampered).is_err());
+
+        // A share consistent with itself but not with the package
+        let mut tampered = shares[..2].to_vec();
+        tampered[0].secret_key = SignerSecretKey(Scalar::<TestnetV0>::rand(rng));
+        tampered[0].public_key = SignerPublicKey(Network::g_scalar_multiply(&tampered[0].secret_key.0));
+        assert!(reconstruct_secret(&package, &tampered).is_err());
+
+        // A share of another group
+        let (other_shares, _, _) = group(rng);
+        assert!(reconstruct_secret(&package, &[shares[0].clone(), other_shares[1].clone()]).is_err());
+
+        // A recovery share that does not rebuild the seed
+        let mut tampered = shares[..2].to_vec();
+        tampered[0].recovery_share = tampered[0].recovery_share.map(|share| share + Field::<TestnetV0>::one());
+        assert!(reconstruct_secret(&package, &tampered).is_ok());
+        assert!(reconstruct_private_key(&package, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_tampered_package_is_rejected() {
+        let rng = &mut TestRng::default();
+        let (shares, package, _) = group(rng);
+        package.verify().unwrap();
+
+        // A public key swapped for another participant's
+        let mut tampered = package.clone();
+        let (first, second) = (shares[0].participant_index, shares[1].participant_index);
+        let public_key = tampered.public_keys[&second].clone();
+        tampered.public_keys.insert(first, public_key);
+        assert!(tampered.verify().is_err());
+
+        // A commitment that does not start with the group public key
+        let mut tampered = package.clone();
+        tampered.commitment[0] = tampered.commitment[1];
+        assert!(tampered.verify().is_err());
+
+        // Another group public key
+        let mut tampered = package.clone();
+        tampered.group_public_key = GroupPublicKey(Network::g_scalar_multiply(&Scalar::<TestnetV0>::rand(rng)));
+        assert!(tampered.verify().is_err());
+
+        // A higher threshold than the commitment was made for
+        let mut tampered = package.clone();
+        tampered.commitment.push(Network::g_scalar_multiply(&Scalar::<TestnetV0>::rand(rng)));
+        assert!(tampered.verify().is_err());
+
+        // A share index owned by two participants
+        let mut tampered = package.clone();
+        tampered.participant_shares.insert(second, vec![first, second]);
+        assert!(tampered.verify().is_err());
+
+        // A tampered package is refused before its keys are used
+        assert!(tampered.public_keys().is_err());
+        assert!(reconstruct_secret(&tampered, &shares[..2]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use snarkvm_console_account::PrivateKey;
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types_scalar::{anyhow, ensure, Result};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload as AeadPayload},
+    Key,
+    XChaCha20Poly1305,
+    XNonce,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{keys::SignerShare, utils::write_private};
+
+/// The version of the keystore format, keystores of any other version are rejected
+pub const KEYSTORE_VERSION: u16 = 2;
+/// The associated data authenticated with every keystore ciphertext
+const KEYSTORE_AAD: &[u8] = b"AleoFrostKeystore";
+/// The length of the Argon2id salt in bytes
+const SALT_LENGTH: usize = 16;
+/// The length of the XChaCha20-Poly1305 nonce in bytes
+const NONCE_LENGTH: usize = 24;
+
+/// The secrets a signer keeps encrypted at rest
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignerSecrets {
+    // The signer's key share
+    pub share: SignerShare,
+    // The signer's identity key, authenticating its round messages
+    pub identity_key: PrivateKey<TestnetV0>,
+}
+
+/// The Argon2id cost parameters of a keystore
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KdfParams {
+    // The memory used, in KiB
+    pub memory_kib: u32,
+    // The number of passes over the memory
+    pub iterations: u32,
+    // The number of lanes
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    // 64 MiB and 3 passes, a few hundred milliseconds on a signer's machine
+    fn default() -> Self {
+        Self { memory_kib: 64 * 1024, iterations: 3, parallelism: 1 }
+    }
+}
+
+/// A signer's secrets encrypted under a password
+///
+/// - key = Argon2id(password, salt) with the keystore's cost parameters
+/// - ciphertext = XChaCha20-Poly1305(key, nonce, secrets JSON), whose tag detects a wrong
+///   password or a modified keystore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Keystore {
+    pub version: u16,
+    pub kdf: KdfParams,
+    pub salt: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl Keystore {
+    // Encrypt the signer's secrets under the password with the default cost parameters
+    pub fn encrypt<R: Rng>(secrets: &SignerSecrets, password: &str, rng: &mut R) -> Result<Self> {
+        Self::encrypt_with_params(secrets, password, KdfParams::default(), rng)
+    }
+
+    // Encrypt the signer's secrets under the password
+    pub fn encrypt_with_params<R: Rng>(secrets: &SignerSecrets, password: &str, kdf: KdfParams, rng: &mut R) -> Result<Self> {
+        let mut salt = [0u8; SALT_LENGTH];
+        rng.fill(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill(&mut nonce);
+
+        let key = derive_key(password, &salt, kdf)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+        let plaintext = serde_json::to_vec(secrets)?;
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), AeadPayload { msg: &plaintext, aad: KEYSTORE_AAD })
+            .map_err(|_| anyhow!("Failed to encrypt the keystore"))?;
+
+        Ok(Self { version: KEYSTORE_VERSION, kdf, salt: salt.to_vec(), nonce: nonce.to_vec(), ciphertext })
+    }
+
+    // Decrypt the signer's secrets, rejecting a wrong password
+    pub fn decrypt(&self, password: &str) -> Result<SignerSecrets> {
+        ensure!(self.version == KEYSTORE_VERSION, "Unsupported keystore version {}, expected {KEYSTORE_VERSION}", self.version);
+        ensure!(self.salt.len() == SALT_LENGTH && self.nonce.len() == NONCE_LENGTH, "Malformed keystore");
+
+        let key = derive_key(password, &self.salt, self.kdf)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&self.nonce), AeadPayload { msg: &self.ciphertext, aad: KEYSTORE_AAD })
+            .map_err(|_| anyhow!("Wrong password or corrupted keystore"))?;
+        let secrets: SignerSecrets = serde_json::from_slice(&plaintext)?;
+        ensure!(secrets.share.is_valid(), "The decrypted key share does not match its commitment");
+
+        Ok(secrets)
+    }
+
+    // Read a keystore from a JSON file on disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    // Write the keystore to disk as JSON, readable by its owner only
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_private(path.as_ref(), serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+// Derive the 256-bit encryption key from the password with Argon2id
+fn derive_key(password: &str, salt: &[u8], kdf: KdfParams) -> Result<[u8; 32]> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|error| anyhow!("Invalid keystore parameters: {error}"))?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|error| anyhow!("Failed to derive the keystore key: {error}"))?;
+
+    Ok(key)
+}
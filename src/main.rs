@@ -1,17 +1,20 @@
 use std::collections::HashMap;
 
-use snarkvm_console_account::{private_key::*, compute_key::*, signature::*, Address, FromStr, ToFields, Zero};
+use snarkvm_console_account::{private_key::*, compute_key::*, signature::*, Address, FromStr, Zero};
 use snarkvm_console_network::{Network, TestnetV0};
-use snarkvm_console_program::Value;
+use snarkvm_console_program::{Identifier as FunctionName, Plaintext, ProgramID, Value, ValueType};
 use snarkvm_console_types::Scalar;
 use snarkvm_console_types_scalar::TestRng;
+use snarkvm_synthesizer_process::Process;
 
-use crate::{keys::trusted_keygen, preprocess::preprocess, frost::PartialThresholdSignature, utils::{calculate_binding_value, calculate_group_commitment}};
-
-mod keys;
-mod preprocess;
-mod utils;
-mod frost;
+use aleo_frost::{
+    add_recovery_shares, bytes_signing_package, calculate_binding_value, calculate_group_commitment, fee_public_signing_package,
+    message_binding_message, message_hash, plaintext_signing_package, preprocess, read_audit_log, reconstruct_private_key,
+    reconstruct_secret, sign_bytes, sign_plaintext, threshold_authorize, trusted_keygen, verify_audit_log, verify_bytes, verify_plaintext,
+    AggregationResult, AuditEvent, AuditEventKind, AuditLog, AuditOutcome, GroupConfig, Identifier, MessageKind,
+    PeerSession, PublicKeyPackage, RequestSigningPackage, SignerShare, SigningMessage, SigningPackage, SigningPolicy, SigningTarget, SpendingLedger,
+    SpendingLimit, Summarize, ThresholdAccount,
+};
 
 fn main() {
     let rng = &mut TestRng::default();
@@ -20,8 +23,10 @@ fn main() {
     let address = Address::<TestnetV0>::try_from(compute_key).unwrap();
 
     // message to verify
-    let message = Value::<TestnetV0>::from_str("{ recipient: aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew, amount: 10u128 }").unwrap().to_fields().unwrap();
+    let signing_message = SigningMessage::parse_value("{ recipient: aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew, amount: 10u128 }").unwrap();
+    let message = signing_message.to_fields().unwrap();
     println!("message: {:?}", message);
+    println!("decoded message: {}", SigningMessage::decode(&message, signing_message.kind()).unwrap());
 
     // vanilla sign & verify:
 
@@ -33,19 +38,38 @@ fn main() {
     // FROST sign & verify:
 
     // construct FROST multisig shares from the private key
-    let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng);
+    let (mut shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+    // Split the account seed among the shares so the full private key can be recovered
+    add_recovery_shares(&mut shares, &private_key, rng).unwrap();
     println!("key shares: {:?}", shares);
     println!("public keys: {:?}", public_keys);
 
+    // Tie the public keys to the commitment so a tampered package is detected before signing
+    let public_key_package = PublicKeyPackage::new(public_keys.clone(), shares[0].commitment.clone());
+    public_key_package.verify().unwrap();
+    println!("public key package verified against the commitment");
+
+    // Back up a key share as a word mnemonic and restore it from the public key package
+    let mnemonic = shares[0].to_mnemonic().unwrap();
+    println!("mnemonic for share 1: {}", mnemonic);
+    let restored_share = SignerShare::from_mnemonic(&mnemonic, &public_key_package).unwrap();
+    println!("Does the restored share match share 1? {:?}", restored_share == shares[0]);
+
     // Confirming that we can reconstruct the sk_sig from the new shares
-    // let reconstructed_secret = reconstruct_secret(&shares).unwrap();
-    // println!("Reconstructed secret is {:?}", reconstructed_secret);
-    // println!("Does this match sk_sig? {:?}", reconstructed_secret.0 == private_key.sk_sig());
+    let reconstructed_secret = reconstruct_secret(&public_key_package, &shares[..2]).unwrap();
+    println!("Reconstructed secret is {:?}", reconstructed_secret);
+    println!("Does this match sk_sig? {:?}", reconstructed_secret.0 == private_key.sk_sig());
+
+    // Emergency recovery of the full private key from the recovery shares of threshold participants
+    let recovered_private_key = reconstruct_private_key(&public_key_package, &shares[..2]).unwrap();
+    println!("Does the recovered private key match? {:?}", recovered_private_key == private_key);
 
     // FROST round 1: Choosing 2 signers and computing preprocess round to generate signing nonces and singing commitments for signers 1 and 2
     println!("------- Round 1: Preprocessing  -------");
-    let (signing_nonces_1, signing_commitments_1) = preprocess(1, 1, rng);
-    let (signing_nonces_2, signing_commitments_2) = preprocess(1, 2, rng);
+    let participant_1 = Identifier::from_u64(1).unwrap();
+    let participant_2 = Identifier::from_u64(2).unwrap();
+    let (signing_nonces_1, signing_commitments_1) = preprocess(1, participant_1, rng);
+    let (signing_nonces_2, signing_commitments_2) = preprocess(1, participant_2, rng);
     println!("computed signing nonces and commitments");
 
     // Computing B from the two signing commitments
@@ -54,23 +78,14 @@ fn main() {
     // Constructing Partial signatures for the two signers
     println!("------- RoundD 2: Partial Signing & Aggregation  -------");
     println!("computing partial signaturess for signers 1 & 2 for 2/3 threshold...");
-    let signer_share_1 = shares.iter().find(|share| share.participant_index == 1).unwrap();
-    let partial_sig_1 = PartialThresholdSignature::new_partial_sig(
-      &signer_share_1,
-      &signing_nonces_1[0],
-      signing_commitments_b.clone(),
-      message.clone(),
-      compute_key.pr_sig()
-    ).unwrap();
+    // Each signer decodes the message from the package before signing it
+    let signing_package = SigningPackage::new(signing_commitments_b.clone(), signing_message.clone());
+    println!("approving:\n{}", signing_package.summary().unwrap());
+    let signer_share_1 = shares.iter().find(|share| share.participant_index == participant_1).unwrap();
+    let partial_sig_1 = signing_package.partial_sign(signer_share_1, &signing_nonces_1[0], compute_key.pr_sig()).unwrap();
     println!("partial signature 1: {:?}", partial_sig_1);
-    let signer_share_2 = shares.iter().find(|share| share.participant_index == 2).unwrap();
-    let partial_sig_2 = PartialThresholdSignature::new_partial_sig(
-      &signer_share_2,
-      &signing_nonces_2[0],
-      signing_commitments_b.clone(),
-      message.clone(),
-      compute_key.pr_sig()
-    ).unwrap();
+    let signer_share_2 = shares.iter().find(|share| share.participant_index == participant_2).unwrap();
+    let partial_sig_2 = signing_package.partial_sign(signer_share_2, &signing_nonces_2[0], compute_key.pr_sig()).unwrap();
     println!("partial signature 2: {:?}", partial_sig_2);
 
     // note: this is the point where threshold is reached
@@ -79,9 +94,11 @@ fn main() {
     let partial_signatures = vec![partial_sig_1, partial_sig_2];
 
     // Construct the aggregated response, threshold challenge and the complete signature
-    let mut binding_values: HashMap<u64, Scalar<TestnetV0>> = HashMap::with_capacity(signing_commitments_b.len());
+    // The binding values of a message package are computed over the domain-separated message
+    let binding_message = message_binding_message(&message);
+    let mut binding_values: HashMap<Identifier, Scalar<TestnetV0>> = HashMap::with_capacity(signing_commitments_b.len());
     for commitment in &signing_commitments_b {
-      let rho_i = calculate_binding_value(commitment.participant_index, &signing_commitments_b, &message);
+      let rho_i = calculate_binding_value(commitment.participant_index, &signing_commitments_b, &binding_message);
       binding_values.insert(commitment.participant_index, rho_i);
     }
     println!("binding values rho_i: {:?}", binding_values);
@@ -124,4 +141,167 @@ fn main() {
       true => println!("verified 🟢"),
       false => println!("verified 🔴")
     };
+
+    // The multisig account holding the group public key, pr_sig and sk_tag -- no view key
+    let account = ThresholdAccount::from_private_key(public_keys.clone(), &private_key).unwrap();
+
+    // Threshold signing of a Leo struct, verifiable on-chain with signature::verify
+    println!("------- Leo Message Signing -------");
+    let plaintext = Plaintext::<TestnetV0>::from_str("{ recipient: aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew, amount: 10u128 }").unwrap();
+    let (plaintext_nonces_1, plaintext_commitments_1) = preprocess(1, participant_1, rng);
+    let (plaintext_nonces_2, plaintext_commitments_2) = preprocess(1, participant_2, rng);
+    let plaintext_package = plaintext_signing_package(plaintext.clone(), vec![plaintext_commitments_1[0], plaintext_commitments_2[0]]);
+    let plaintext_partial_signatures = vec![
+      plaintext_package.partial_sign(signer_share_1, &plaintext_nonces_1[0], compute_key.pr_sig()).unwrap(),
+      plaintext_package.partial_sign(signer_share_2, &plaintext_nonces_2[0], compute_key.pr_sig()).unwrap(),
+    ];
+    let plaintext_signature = sign_plaintext(&plaintext_package, &account, &plaintext_partial_signatures).unwrap();
+    println!("plaintext signature: {}", plaintext_signature);
+    match verify_plaintext(&plaintext_signature, &address, &plaintext) {
+      true => println!("plaintext signature verified 🟢"),
+      false => println!("plaintext signature verified 🔴")
+    };
+
+    // Threshold signing of a wallet-style login challenge, verifiable with Signature::verify_bytes
+    println!("------- Byte String Signing -------");
+    let challenge = b"Sign in to aleo-frost: nonce 8f2c41";
+    let (bytes_nonces_1, bytes_commitments_1) = preprocess(1, participant_1, rng);
+    let (bytes_nonces_2, bytes_commitments_2) = preprocess(1, participant_2, rng);
+    let bytes_package = bytes_signing_package(challenge, vec![bytes_commitments_1[0], bytes_commitments_2[0]]);
+    // Raw bytes are opaque, so the signers' policies must allow them explicitly
+    let bytes_policy = SigningPolicy { allowed_message_kinds: Some(vec![MessageKind::Bytes]), ..Default::default() };
+    let mut bytes_ledger = SpendingLedger::open(std::env::temp_dir().join("aleo-frost-bytes-spending.json")).unwrap();
+    let bytes_partial_signatures = vec![
+      bytes_policy.partial_sign(&bytes_package, &mut bytes_ledger, signer_share_1, &bytes_nonces_1[0], compute_key.pr_sig()).unwrap(),
+      bytes_policy.partial_sign(&bytes_package, &mut bytes_ledger, signer_share_2, &bytes_nonces_2[0], compute_key.pr_sig()).unwrap(),
+    ];
+    let bytes_signature = sign_bytes(&bytes_package, &account, &bytes_partial_signatures).unwrap();
+    match verify_bytes(&bytes_signature, &address, challenge) {
+      true => println!("byte string signature verified 🟢"),
+      false => println!("byte string signature verified 🔴")
+    };
+
+    // Threshold signing of an Aleo function call Request from the multisig account
+    println!("------- Threshold Request Signing -------");
+    let (request_nonces_1, request_commitments_1) = preprocess(1, participant_1, rng);
+    let (request_nonces_2, request_commitments_2) = preprocess(1, participant_2, rng);
+    let mut request_package = RequestSigningPackage::new(
+      ProgramID::from_str("credits.aleo").unwrap(),
+      FunctionName::from_str("transfer_public").unwrap(),
+      vec![
+        Value::from_str("aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew").unwrap(),
+        Value::from_str("10u64").unwrap(),
+      ],
+      vec![ValueType::from_str("address.public").unwrap(), ValueType::from_str("u64.public").unwrap()],
+      vec![request_commitments_1[0], request_commitments_2[0]],
+    ).unwrap();
+    // Every signer sends its nonce shares, from which the transition view key is combined
+    let request_nonce_shares_1 = request_package.nonce_shares_for(&account, signer_share_1, &request_nonces_1[0], rng).unwrap();
+    let request_nonce_shares_2 = request_package.nonce_shares_for(&account, signer_share_2, &request_nonces_2[0], rng).unwrap();
+    request_package.add_nonce_shares(request_nonce_shares_1).unwrap();
+    request_package.add_nonce_shares(request_nonce_shares_2).unwrap();
+    println!("approving:\n{}", request_package.summary().unwrap());
+    // Signer 1 only signs transfers of up to 1 credit to known recipients, and 10,000 credits per 24h
+    let policy = SigningPolicy {
+      allowed_functions: Some(vec!["credits.aleo/transfer_public".to_string(), "credits.aleo/fee_public".to_string()]),
+      max_u64_amount: Some(1_000_000),
+      allowed_recipients: Some(vec![Address::from_str("aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew").unwrap()]),
+      spending_limits: vec![SpendingLimit {
+        program_id: ProgramID::from_str("credits.aleo").unwrap(),
+        function_name: None,
+        window_seconds: 86_400,
+        max_amount: 10_000_000_000,
+      }],
+      ..Default::default()
+    };
+    let mut ledger = SpendingLedger::open(std::env::temp_dir().join("aleo-frost-signer-1-spending.json")).unwrap();
+    let request_partial_signatures = vec![
+      policy.partial_sign_request(&request_package, &mut ledger, &account, signer_share_1, &request_nonces_1[0]).unwrap(),
+      request_package.partial_sign(&account, signer_share_2, &request_nonces_2[0]).unwrap(),
+    ];
+    let request = request_package.aggregate(&account, &request_partial_signatures).unwrap();
+    match request.verify(&request_package.input_types) {
+      true => println!("request verified 🟢"),
+      false => println!("request verified 🔴")
+    };
+
+    // Record the keygen and the aggregation in the coordinator's audit log, signed with its Aleo identity key
+    let coordinator_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+    let audit_path = std::env::temp_dir().join("aleo-frost-audit.jsonl");
+    let coordinator_address = Address::try_from(&coordinator_key).unwrap();
+    let _ = std::fs::remove_file(&audit_path);
+    let mut audit_log = AuditLog::open(&audit_path, coordinator_address).unwrap();
+    audit_log.append(&coordinator_key, AuditEvent::keygen("keygen-1", &public_keys, AuditOutcome::Success).unwrap(), rng).unwrap();
+    audit_log.append(&coordinator_key, AuditEvent {
+      kind: AuditEventKind::Aggregation,
+      session_id: "request-1".to_string(),
+      message_hash: Some(message_hash(&request_package.binding_message().unwrap()).unwrap()),
+      participants: vec![participant_1, participant_2],
+      commitments: request_package.signing_commitments.clone(),
+      outcome: AuditOutcome::Success,
+    }, rng).unwrap();
+    match verify_audit_log(&read_audit_log(&audit_path).unwrap(), &coordinator_address, Some(audit_log.head())) {
+      Ok(..) => println!("audit log verified 🟢"),
+      Err(..) => println!("audit log verified 🔴")
+    };
+
+    // Two signers agree on the package and sign the message without a coordinator
+    println!("------- Peer Signing -------");
+    let peer_keys = [PrivateKey::<TestnetV0>::new(rng).unwrap(), PrivateKey::<TestnetV0>::new(rng).unwrap()];
+    let peer_config = GroupConfig {
+      coordinator: Address::try_from(&coordinator_key).unwrap(),
+      participants: HashMap::from([
+        (participant_1, Address::try_from(&peer_keys[0]).unwrap()),
+        (participant_2, Address::try_from(&peer_keys[1]).unwrap()),
+      ]),
+    };
+    let mut peer_sessions = Vec::new();
+    for participant in [participant_1, participant_2] {
+      let path = std::env::temp_dir().join(format!("aleo-frost-peer-{participant}.json"));
+      let _ = std::fs::remove_file(&path);
+      let target = SigningTarget::Message(signing_message.clone());
+      peer_sessions.push(PeerSession::new(path, "peer-1", participant, vec![participant_1, participant_2], target, 600, rng).unwrap());
+    }
+    // Each round is broadcast to the other peer, the echoes show both peers received the same commitments
+    let peer_announcements = [peer_sessions[0].announce(&peer_keys[0], rng).unwrap(), peer_sessions[1].announce(&peer_keys[1], rng).unwrap()];
+    peer_sessions[0].receive(&peer_announcements[1], &peer_config, &account).unwrap();
+    peer_sessions[1].receive(&peer_announcements[0], &peer_config, &account).unwrap();
+    let peer_echoes = [peer_sessions[0].echo(&peer_keys[0], rng).unwrap(), peer_sessions[1].echo(&peer_keys[1], rng).unwrap()];
+    peer_sessions[0].receive(&peer_echoes[1], &peer_config, &account).unwrap();
+    peer_sessions[1].receive(&peer_echoes[0], &peer_config, &account).unwrap();
+    let mut peer_ledger = SpendingLedger::open(std::env::temp_dir().join("aleo-frost-peer-spending.json")).unwrap();
+    let peer_partials = [
+      peer_sessions[0].sign(&account, signer_share_1, &SigningPolicy::default(), &mut peer_ledger, &peer_keys[0], rng).unwrap(),
+      peer_sessions[1].sign(&account, signer_share_2, &SigningPolicy::default(), &mut peer_ledger, &peer_keys[1], rng).unwrap(),
+    ];
+    peer_sessions[0].receive(&peer_partials[1], &peer_config, &account).unwrap();
+    peer_sessions[1].receive(&peer_partials[0], &peer_config, &account).unwrap();
+    match peer_sessions[0].aggregate(&account).unwrap() {
+      AggregationResult::Signature(signature) if signature.verify(&address, &message) => println!("peer signature verified 🟢"),
+      _ => println!("peer signature verified 🔴")
+    };
+
+    // Threshold-signed Authorization of the transfer and its fee, ready for an online prover
+    println!("------- Threshold Authorization -------");
+    let process = Process::<TestnetV0>::load().unwrap();
+    let authorization = threshold_authorize(&process, &request_package, &account, &request_partial_signatures, rng).unwrap();
+    let (fee_nonces_1, fee_commitments_1) = preprocess(1, participant_1, rng);
+    let (fee_nonces_2, fee_commitments_2) = preprocess(1, participant_2, rng);
+    let mut fee_package = fee_public_signing_package(
+      10_000,
+      0,
+      authorization.to_execution_id().unwrap(),
+      vec![fee_commitments_1[0], fee_commitments_2[0]],
+    ).unwrap();
+    let fee_nonce_shares_1 = fee_package.nonce_shares_for(&account, signer_share_1, &fee_nonces_1[0], rng).unwrap();
+    let fee_nonce_shares_2 = fee_package.nonce_shares_for(&account, signer_share_2, &fee_nonces_2[0], rng).unwrap();
+    fee_package.add_nonce_shares(fee_nonce_shares_1).unwrap();
+    fee_package.add_nonce_shares(fee_nonce_shares_2).unwrap();
+    let fee_partial_signatures = vec![
+      fee_package.partial_sign(&account, signer_share_1, &fee_nonces_1[0]).unwrap(),
+      fee_package.partial_sign(&account, signer_share_2, &fee_nonces_2[0]).unwrap(),
+    ];
+    let fee_authorization = threshold_authorize(&process, &fee_package, &account, &fee_partial_signatures, rng).unwrap();
+    println!("execution authorization: {}", authorization);
+    println!("fee authorization: {}", fee_authorization);
 }
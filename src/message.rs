@@ -0,0 +1,152 @@
+use snarkvm_console_account::{signature::*, Address, ToFields};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::Plaintext;
+use snarkvm_console_types_scalar::{bail, Result};
+
+use crate::{account::*, frost::*, generate_message::SigningMessage, preprocess::*};
+
+/// Build the signing package for a Leo/Aleo plaintext such as a struct, array or literal
+///
+/// The plaintext is signed as `Plaintext::to_fields`, the message used by the `sign.verify`
+/// instruction, so the signature can be checked on-chain with `signature::verify(sig, addr, msg)`.
+pub fn plaintext_signing_package(
+    plaintext: Plaintext<TestnetV0>,
+    signing_commitments: Vec<SigningCommitment>,
+) -> SigningPackage {
+    SigningPackage::new(signing_commitments, SigningMessage::from_plaintext(plaintext))
+}
+
+/// Aggregate the signers' partial signatures on a plaintext into the account's signature
+///
+/// The signature displays as a `sign1…` literal that can be passed as a program input.
+pub fn sign_plaintext(
+    package: &SigningPackage,
+    account: &ThresholdAccount,
+    partial_signatures: &[PartialThresholdSignature],
+) -> Result<Signature<TestnetV0>> {
+    if !matches!(package.message, SigningMessage::Plaintext(..)) {
+        bail!("Expected a plaintext message, found '{}'", package.message);
+    }
+
+    package.aggregate(account, partial_signatures)
+}
+
+// Verify a signature on a plaintext the same way as the `sign.verify` instruction
+pub fn verify_plaintext(
+    signature: &Signature<TestnetV0>,
+    address: &Address<TestnetV0>,
+    plaintext: &Plaintext<TestnetV0>,
+) -> bool {
+    match plaintext.to_fields() {
+        Ok(message) => signature.verify(address, &message),
+        Err(..) => false,
+    }
+}
+
+/// Build the signing package for a byte string such as a UTF-8 login challenge
+///
+/// The bytes are packed into fields the same way as `PrivateKey::sign_bytes`.
+pub fn bytes_signing_package(
+    bytes: &[u8],
+    signing_commitments: Vec<SigningCommitment>,
+) -> SigningPackage {
+    SigningPackage::new(signing_commitments, SigningMessage::from_bytes(bytes))
+}
+
+/// Aggregate the signers' partial signatures on a byte string into the account's signature
+///
+/// The signature verifies with `Signature::verify_bytes`, as a wallet-signed message would.
+pub fn sign_bytes(
+    package: &SigningPackage,
+    account: &ThresholdAccount,
+    partial_signatures: &[PartialThresholdSignature],
+) -> Result<Signature<TestnetV0>> {
+    if !matches!(package.message, SigningMessage::Bytes(..)) {
+        bail!("Expected a byte string message, found '{}'", package.message);
+    }
+
+    package.aggregate(account, partial_signatures)
+}
+
+// Verify a signature on a byte string the same way as `Signature::verify_bytes`
+pub fn verify_bytes(
+    signature: &Signature<TestnetV0>,
+    address: &Address<TestnetV0>,
+    bytes: &[u8],
+) -> bool {
+    signature.verify_bytes(address, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::keys::*;
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::TestRng;
+    use std::str::FromStr;
+
+    // Sign a package with the first two shares of a fresh 2-of-3 account
+    fn sign(
+        package_of: impl Fn(Vec<SigningCommitment>) -> SigningPackage,
+        raw: bool,
+        rng: &mut TestRng,
+    ) -> (SigningPackage, ThresholdAccount, Vec<PartialThresholdSignature>) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+        let pr_sig = account.compute_key().unwrap().pr_sig();
+
+        let nonces: Vec<SigningNonce> = shares[..2].iter().map(|_| SigningNonce::new(rng)).collect();
+        let commitments = shares[..2].iter().zip(&nonces).map(|(share, nonce)| SigningCommitment::from(share.participant_index, nonce)).collect();
+        let package = package_of(commitments);
+        let partial_signatures = shares[..2]
+            .iter()
+            .zip(&nonces)
+            .map(|(share, nonce)| match raw {
+                true => {
+                    assert!(package.partial_sign(share, nonce, pr_sig).is_err());
+                    package.partial_sign_approved(share, nonce, pr_sig).unwrap()
+                }
+                false => package.partial_sign(share, nonce, pr_sig).unwrap(),
+            })
+            .collect();
+
+        (package, account, partial_signatures)
+    }
+
+    #[test]
+    fn test_plaintext_signature_verifies() {
+        let rng = &mut TestRng::default();
+        let plaintext = Plaintext::<TestnetV0>::from_str("{ amount: 10u64, nonce: 7field }").unwrap();
+        let (package, account, partial_signatures) = sign(|commitments| plaintext_signing_package(plaintext.clone(), commitments), false, rng);
+        let address = account.address().unwrap();
+
+        let signature = sign_plaintext(&package, &account, &partial_signatures).unwrap();
+        assert!(verify_plaintext(&signature, &address, &plaintext));
+        // The message of `sign.verify` on-chain
+        assert!(signature.verify(&address, &plaintext.to_fields().unwrap()));
+        assert!(Signature::<TestnetV0>::from_str(&signature.to_string()).unwrap().verify(&address, &plaintext.to_fields().unwrap()));
+
+        let other = Plaintext::<TestnetV0>::from_str("{ amount: 11u64, nonce: 7field }").unwrap();
+        assert!(!verify_plaintext(&signature, &address, &other));
+        assert!(sign_bytes(&package, &account, &partial_signatures).is_err());
+    }
+
+    #[test]
+    fn test_bytes_signature_verifies() {
+        let rng = &mut TestRng::default();
+        let bytes = b"Sign in to example.com\nnonce: 3f8a";
+        let (package, account, partial_signatures) = sign(|commitments| bytes_signing_package(bytes, commitments), true, rng);
+        let address = account.address().unwrap();
+
+        let signature = sign_bytes(&package, &account, &partial_signatures).unwrap();
+        assert!(verify_bytes(&signature, &address, bytes));
+        assert!(signature.verify_bytes(&address, bytes));
+
+        assert!(!verify_bytes(&signature, &address, b"Sign in to example.org\nnonce: 3f8a"));
+        assert!(!verify_bytes(&signature, &address, &bytes[..bytes.len() - 1]));
+        assert!(sign_plaintext(&package, &account, &partial_signatures).is_err());
+    }
+}
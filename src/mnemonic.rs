@@ -0,0 +1,262 @@
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_types::{Field, Scalar};
+use snarkvm_console_types_scalar::{bail, ensure, FromBits, Result, SizeInBits, SizeInDataBits, ToBits};
+
+use bip39::Language;
+
+use crate::{identifier::Identifier, keys::*};
+
+/// The version of the mnemonic layout, restoring rejects any other version
+const MNEMONIC_VERSION: u8 = 1;
+/// Number of bits of the version
+const VERSION_BITS: usize = 4;
+/// Number of bits of the flags following the version
+const FLAG_BITS: usize = 4;
+/// Flag set when the identifier is a small integer index, encoded in COMPACT_INDEX_BITS
+const COMPACT_INDEX_FLAG: u8 = 1;
+/// Flag set when the share carries its recovery share of the account seed, encoded in a full field element
+const RECOVERY_SHARE_FLAG: u8 = 2;
+/// Number of bits of an identifier built from an integer index below 2^16
+const COMPACT_INDEX_BITS: usize = 16;
+/// Number of bits encoded by a single mnemonic word (the word list has 2^11 entries)
+const BITS_PER_WORD: usize = 11;
+/// Number of checksum bits appended to the payload
+const CHECKSUM_BITS: usize = 2 * BITS_PER_WORD;
+/// Domain separator for the mnemonic checksum
+const CHECKSUM_DOMAIN: &str = "AleoFrostShareMnemonic";
+
+/// Computes the checksum over the bits preceding it
+///
+/// checksum = first CHECKSUM_BITS bits of H(domain, payload bits packed into fields)
+fn checksum(payload: &[bool]) -> Result<Vec<bool>> {
+    let mut preimage = vec![Field::<TestnetV0>::new_domain_separator(CHECKSUM_DOMAIN)];
+    for chunk in payload.chunks(Field::<TestnetV0>::size_in_data_bits()) {
+        preimage.push(Field::from_bits_le(chunk)?);
+    }
+    let hash = TestnetV0::hash_psd8(&preimage)?;
+
+    Ok(hash.to_bits_le()[..CHECKSUM_BITS].to_vec())
+}
+
+// The little-endian bits of the lowest `num_bits` bits of a number
+fn number_to_bits(number: u64, num_bits: usize) -> Vec<bool> {
+    (0..num_bits).map(|i| (number >> i) & 1 == 1).collect()
+}
+
+fn bits_to_number(bits: &[bool]) -> u64 {
+    bits.iter().rev().fold(0u64, |acc, bit| (acc << 1) | *bit as u64)
+}
+
+impl SignerShare {
+    /// Encodes the participant identifier and secret key share as a checksummed word mnemonic
+    ///
+    /// The layout follows the spirit of SLIP-39 for a single share:
+    /// [version (4 bits) || flags (4 bits) || identifier || secret_key (251 bits) || recovery_share (253 bits, optional)
+    ///  || checksum (22 bits) || zero padding]
+    /// with every 11 bits mapped to a word of the BIP-39 English word list.
+    ///
+    /// Identifiers built from an index below 2^16 take 16 bits, so such a share fits in 27 words;
+    /// identifiers derived from labels or addresses take the full 251 bits, 49 words in total.
+    /// A share holding a recovery share of the seed is 23 words longer.
+    ///
+    /// The mnemonic only holds the secret part of the share, the public commitments
+    /// have to be restored from the group's `PublicKeyPackage`.
+    pub fn to_mnemonic(&self) -> Result<String> {
+        let identifier_bits = self.participant_index.to_scalar().to_bits_le();
+        let compact = identifier_bits[COMPACT_INDEX_BITS..].iter().all(|bit| !bit);
+
+        let mut flags = 0;
+        if compact {
+            flags |= COMPACT_INDEX_FLAG;
+        }
+        if self.recovery_share.is_some() {
+            flags |= RECOVERY_SHARE_FLAG;
+        }
+
+        let mut bits = number_to_bits(MNEMONIC_VERSION as u64, VERSION_BITS);
+        bits.extend(number_to_bits(flags as u64, FLAG_BITS));
+        match compact {
+            true => bits.extend(&identifier_bits[..COMPACT_INDEX_BITS]),
+            false => bits.extend(identifier_bits),
+        }
+        bits.extend(self.secret_key.0.to_bits_le());
+        if let Some(recovery_share) = self.recovery_share {
+            bits.extend(recovery_share.to_bits_le());
+        }
+        bits.extend(checksum(&bits)?);
+
+        // Pad with zeros to a whole number of words
+        while bits.len() % BITS_PER_WORD != 0 {
+            bits.push(false);
+        }
+
+        let word_list = Language::English.word_list();
+        let words: Vec<&str> = bits.chunks(BITS_PER_WORD).map(|chunk| word_list[bits_to_number(chunk) as usize]).collect();
+
+        Ok(words.join(" "))
+    }
+
+    /// Restores a full signer share from its mnemonic and the group's public key package
+    ///
+    /// The restored share is rejected if the version is unknown, if the checksum does not match,
+    /// if the package fails verification, if the secret key is not consistent with the commitment,
+    /// or if the public key differs from the one registered in the package.
+    pub fn from_mnemonic(mnemonic: &str, public_key_package: &PublicKeyPackage) -> Result<Self> {
+        public_key_package.verify()?;
+
+        let mut bits = Vec::new();
+        for word in mnemonic.split_whitespace() {
+            let word_index = match Language::English.find_word(&word.to_lowercase()) {
+                Some(word_index) => word_index,
+                None => bail!("'{word}' is not in the mnemonic word list"),
+            };
+            bits.extend(number_to_bits(word_index as u64, BITS_PER_WORD));
+        }
+        ensure!(bits.len() >= VERSION_BITS + FLAG_BITS, "The mnemonic is empty");
+
+        let version = bits_to_number(&bits[..VERSION_BITS]) as u8;
+        ensure!(version == MNEMONIC_VERSION, "Unsupported mnemonic version {version}, expected {MNEMONIC_VERSION}");
+        let flags = bits_to_number(&bits[VERSION_BITS..VERSION_BITS + FLAG_BITS]) as u8;
+        ensure!(flags & !(COMPACT_INDEX_FLAG | RECOVERY_SHARE_FLAG) == 0, "Unknown mnemonic flags {flags:#06b}");
+
+        let scalar_bits = Scalar::<TestnetV0>::size_in_bits();
+        let identifier_bits = match flags & COMPACT_INDEX_FLAG != 0 {
+            true => COMPACT_INDEX_BITS,
+            false => scalar_bits,
+        };
+        let recovery_bits = match flags & RECOVERY_SHARE_FLAG != 0 {
+            true => Field::<TestnetV0>::size_in_bits(),
+            false => 0,
+        };
+        let payload_bits = VERSION_BITS + FLAG_BITS + identifier_bits + scalar_bits + recovery_bits;
+        let num_words = (payload_bits + CHECKSUM_BITS + BITS_PER_WORD - 1) / BITS_PER_WORD;
+        ensure!(bits.len() == num_words * BITS_PER_WORD, "Expected {num_words} words in the mnemonic, found {}", bits.len() / BITS_PER_WORD);
+        ensure!(bits[payload_bits + CHECKSUM_BITS..].iter().all(|bit| !bit), "Invalid mnemonic padding");
+        ensure!(checksum(&bits[..payload_bits])? == bits[payload_bits..payload_bits + CHECKSUM_BITS], "Invalid mnemonic checksum");
+
+        let mut position = VERSION_BITS + FLAG_BITS;
+        let participant_index = match identifier_bits == COMPACT_INDEX_BITS {
+            true => Identifier::from_u64(bits_to_number(&bits[position..position + identifier_bits]))?,
+            false => Identifier::new(Scalar::<TestnetV0>::from_bits_le(&bits[position..position + identifier_bits])?)?,
+        };
+        position += identifier_bits;
+        let secret_key = Scalar::<TestnetV0>::from_bits_le(&bits[position..position + scalar_bits])?;
+        position += scalar_bits;
+        let recovery_share = match recovery_bits {
+            0 => None,
+            _ => Some(Field::<TestnetV0>::from_bits_le(&bits[position..position + recovery_bits])?),
+        };
+
+        let public_key = match public_key_package.public_keys.get(&participant_index) {
+            Some(public_key) => public_key.clone(),
+            None => bail!("Participant {participant_index} is not part of the group"),
+        };
+
+        let participant_id = match public_key_package.participant_shares.iter().find(|(_, indices)| indices.contains(&participant_index)) {
+            Some((participant_id, _)) => *participant_id,
+            None => bail!("Share {participant_index} is not owned by any participant"),
+        };
+
+        let share = SignerShare {
+            participant_index,
+            participant_id,
+            secret_key: SignerSecretKey(secret_key),
+            public_key,
+            group_public_key: public_key_package.group_public_key.clone(),
+            commitment: public_key_package.commitment.clone(),
+            recovery_share,
+        };
+
+        ensure!(share.public_key.0 == TestnetV0::g_scalar_multiply(&secret_key), "The restored secret key does not match the participant's public key");
+        ensure!(share.is_valid(), "The restored share does not match the public commitment");
+
+        Ok(share)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_account::private_key::*;
+    use snarkvm_console_types_scalar::TestRng;
+
+    // The shares of a 2-of-3 group, with compact or derived identifiers, and the group's package
+    fn group_shares(compact: bool, recovery: bool, rng: &mut TestRng) -> (Vec<SignerShare>, PublicKeyPackage) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (mut shares, public_keys) = match compact {
+            true => trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap(),
+            false => {
+                let identifiers = ["alice", "bob", "carol"].map(|label| Identifier::derive(label).unwrap());
+                trusted_keygen_with_identifiers(&identifiers, 2, &private_key.sk_sig(), rng).unwrap()
+            }
+        };
+        if recovery {
+            add_recovery_shares(&mut shares, &private_key, rng).unwrap();
+        }
+        let package = PublicKeyPackage::new(public_keys, shares[0].commitment.clone());
+
+        (shares, package)
+    }
+
+    // Replace the word at the position with another word of the list
+    fn replace_word(mnemonic: &str, position: usize, word_index: impl Fn(u16) -> u16) -> String {
+        let word_list = Language::English.word_list();
+        let mut words: Vec<&str> = mnemonic.split_whitespace().collect();
+        words[position] = word_list[word_index(Language::English.find_word(words[position]).unwrap()) as usize];
+        words.join(" ")
+    }
+
+    #[test]
+    fn test_round_trip_with_every_layout() {
+        let rng = &mut TestRng::default();
+        for (compact, recovery, num_words) in [(true, false, 27), (true, true, 50), (false, false, 49), (false, true, 72)] {
+            let (shares, package) = group_shares(compact, recovery, rng);
+            for share in &shares {
+                let mnemonic = share.to_mnemonic().unwrap();
+                assert_eq!(mnemonic.split_whitespace().count(), num_words);
+                assert_eq!(SignerShare::from_mnemonic(&mnemonic, &package).unwrap(), *share);
+                // Words are matched regardless of case
+                assert_eq!(SignerShare::from_mnemonic(&mnemonic.to_uppercase(), &package).unwrap(), *share);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_mnemonic_is_rejected() {
+        let rng = &mut TestRng::default();
+        let (shares, package) = group_shares(true, true, rng);
+        let mnemonic = shares[0].to_mnemonic().unwrap();
+
+        // A changed word fails the checksum
+        let tampered = replace_word(&mnemonic, 10, |index| index ^ 1);
+        assert!(SignerShare::from_mnemonic(&tampered, &package).unwrap_err().to_string().contains("checksum"));
+
+        // Swapped, missing and unknown words
+        let mut words: Vec<&str> = mnemonic.split_whitespace().collect();
+        words.swap(3, 4);
+        assert!(SignerShare::from_mnemonic(&words.join(" "), &package).is_err());
+        let words: Vec<&str> = mnemonic.split_whitespace().collect();
+        assert!(SignerShare::from_mnemonic(&words[..words.len() - 1].join(" "), &package).is_err());
+        assert!(SignerShare::from_mnemonic(&format!("{mnemonic} notaword"), &package).is_err());
+
+        // The share of another group
+        let (_, other_package) = group_shares(true, true, rng);
+        assert!(SignerShare::from_mnemonic(&mnemonic, &other_package).is_err());
+    }
+
+    #[test]
+    fn test_unknown_version_is_rejected() {
+        let rng = &mut TestRng::default();
+        let (shares, package) = group_shares(true, false, rng);
+        let mnemonic = shares[0].to_mnemonic().unwrap();
+
+        // The version is the lowest 4 bits of the first word
+        let tampered = replace_word(&mnemonic, 0, |index| (index & !0xf) | (MNEMONIC_VERSION as u16 + 1));
+        assert!(SignerShare::from_mnemonic(&tampered, &package).unwrap_err().to_string().contains("version"));
+
+        // So are unknown flags
+        let tampered = replace_word(&mnemonic, 0, |index| index | 0x80);
+        assert!(SignerShare::from_mnemonic(&tampered, &package).is_err());
+    }
+}
@@ -1,11 +1,83 @@
+mod account;
+pub use account::*;
+
+mod api;
+pub use api::*;
+
+mod audit;
+pub use audit::*;
+
+mod authorization;
+pub use authorization::*;
+
+mod bundle;
+pub use bundle::*;
+
+mod decryption;
+pub use decryption::*;
+
+mod deployment;
+pub use deployment::*;
+
+mod dleq;
+pub use dleq::*;
+
+mod envelope;
+pub use envelope::*;
+
 mod frost;
 pub use frost::*;
 
+mod gamma;
+pub use gamma::*;
+
+mod generate_message;
+pub use generate_message::*;
+
+mod hierarchy;
+pub use hierarchy::*;
+
+mod identifier;
+pub use identifier::*;
+
+mod identity;
+pub use identity::*;
+
 mod keys;
 pub use keys::*;
 
+mod keystore;
+pub use keystore::*;
+
+mod message;
+pub use message::*;
+
+mod mnemonic;
+pub use mnemonic::*;
+
+mod nonce_pool;
+pub use nonce_pool::*;
+
+mod peer;
+pub use peer::*;
+
+mod policy;
+pub use policy::*;
+
 mod preprocess;
 pub use preprocess::*;
 
+mod request;
+pub use request::*;
+
+mod session;
+pub use session::*;
+
+mod spending;
+pub use spending::*;
+
+mod summary;
+pub use summary::*;
+
 mod utils;
-pub use utils::*;
\ No newline at end of file
+pub use utils::*;
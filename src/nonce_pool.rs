@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+
+use snarkvm_console_types_scalar::{bail, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{preprocess::SigningNonce, utils::write_private};
+
+/// A signer's pool of preprocessed nonces, kept in a file
+///
+/// A nonce is removed from the file before it is handed out, so a nonce is never handed out
+/// twice, even across restarts. The file holds secret nonces and is readable by its owner only.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoncePool {
+    pub nonces: Vec<SigningNonce>,
+    // The file the pool is kept in
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl NoncePool {
+    // Read the pool from a JSON file on disk, starting an empty pool if the file does not exist
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut pool: Self = match path.exists() {
+            true => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+            false => Self::default(),
+        };
+        pool.path = path;
+
+        Ok(pool)
+    }
+
+    // Write the pool to its private file and sync it, so a nonce taken out is gone even after a power loss
+    fn save(&self) -> Result<()> {
+        write_private(&self.path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    // Generate nonces until the pool holds `size` of them
+    pub fn top_up<R: Rng>(&mut self, size: usize, rng: &mut R) -> Result<()> {
+        if self.nonces.len() < size {
+            while self.nonces.len() < size {
+                self.nonces.push(SigningNonce::new(rng));
+            }
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    // Remove a nonce from the pool, saving the pool before returning it
+    pub fn take(&mut self) -> Result<SigningNonce> {
+        let nonce = match self.nonces.pop() {
+            Some(nonce) => nonce,
+            None => bail!("The nonce pool is empty"),
+        };
+        self.save()?;
+
+        Ok(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_taken_nonce_is_gone_after_reopening() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("aleo-frost-nonces-{}.json", rng.gen::<u64>()));
+
+        let mut pool = NoncePool::open(&path).unwrap();
+        pool.top_up(2, rng).unwrap();
+        let nonce = pool.take().unwrap();
+
+        let reopened = NoncePool::open(&path).unwrap();
+        assert_eq!(reopened.nonces.len(), 1);
+        assert!(!reopened.nonces.contains(&nonce));
+
+        // The pool holds secret nonces, so only its owner can read it
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,590 @@
+use std::{collections::HashMap, path::Path};
+
+use snarkvm_console_account::private_key::*;
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{anyhow, bail, ensure, Error, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::*,
+    api::SigningTarget,
+    envelope::*,
+    frost::*,
+    gamma::NonceShares,
+    identifier::Identifier,
+    identity::*,
+    keys::*,
+    policy::SigningPolicy,
+    preprocess::*,
+    request::RequestSigningPackage,
+    session::*,
+    spending::SpendingLedger,
+};
+
+/// Evidence that a participant sent two conflicting messages in a session
+///
+/// Both envelopes are signed with the participant's identity key, so anyone holding the group
+/// config can check the evidence with `verify`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Equivocation {
+    pub participant_index: Identifier,
+    pub first: SignedEnvelope,
+    pub second: SignedEnvelope,
+}
+
+impl Equivocation {
+    // Check that both envelopes are the participant's, in the same session, and conflict
+    pub fn verify(&self, config: &GroupConfig) -> Result<()> {
+        let first = self.first.open(config)?;
+        let second = self.second.open(config)?;
+        ensure!(
+            first.sender == Party::Participant(self.participant_index) && second.sender == first.sender,
+            "The evidence holds envelopes from another party than participant {}", self.participant_index
+        );
+        ensure!(first.session_id == second.session_id, "The evidence holds envelopes from two sessions");
+        ensure!(
+            first.payload != second.payload && (first.sequence == second.sequence || first.payload.name() == second.payload.name()),
+            "The envelopes of participant {} do not conflict", self.participant_index
+        );
+
+        Ok(())
+    }
+}
+
+/// A participant's side of a signing session without a coordinator
+///
+/// 1. Every peer broadcasts its commitment with the hash of the target it was asked to sign
+/// 2. Every peer echoes the signed announcements it received
+/// 3. Once every echo matches its own view, each peer derives the same package, with the
+///    commitments ordered by identifier, and the agreed target
+/// 4. For requests, every peer broadcasts its nonce shares
+/// 5. Every peer broadcasts its partial signature and any peer can aggregate
+///
+/// A peer sending different commitments to different peers shows up as an echoed announcement
+/// that differs from the one received, and signing stops before any nonce is used. Both
+/// announcements carry the peer's signature, so they are kept as an `Equivocation`. Identical
+/// retransmissions are ignored.
+pub struct PeerSession {
+    // The peer's nonce and signing state
+    signer: SignerSession,
+    // Every peer of the session, including this one, ordered by identifier
+    peers: Vec<Identifier>,
+    target: SigningTarget,
+    target_hash: Field<TestnetV0>,
+    // The sequence number of the next envelope broadcast
+    next_sequence: u64,
+    // The envelopes received from each peer, in sequence order
+    received: HashMap<Identifier, Vec<(Envelope, SignedEnvelope)>>,
+    // Every peer's signed announcement, including this peer's
+    announcements: HashMap<Identifier, SignedEnvelope>,
+    commitments: HashMap<Identifier, SigningCommitment>,
+    // The announcements echoed by each peer, ordered by identifier
+    echoes: HashMap<Identifier, Vec<SignedEnvelope>>,
+    nonce_shares: HashMap<Identifier, NonceShares>,
+    partial_signatures: HashMap<Identifier, PartialThresholdSignature>,
+    equivocations: Vec<Equivocation>,
+}
+
+impl PeerSession {
+    // Start a session with a fresh nonce kept in the session file
+    pub fn new<P: AsRef<Path>, R: Rng>(
+        path: P,
+        session_id: &str,
+        participant_index: Identifier,
+        peers: Vec<Identifier>,
+        target: SigningTarget,
+        timeout_seconds: u64,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let mut peers = peers;
+        peers.sort();
+        peers.dedup();
+        ensure!(peers.contains(&participant_index), "Participant {participant_index} is not a peer of session '{session_id}'");
+
+        Ok(Self {
+            signer: SignerSession::create(path, session_id, participant_index, timeout_seconds, rng)?,
+            peers,
+            target_hash: target.hash()?,
+            target,
+            next_sequence: 0,
+            received: HashMap::new(),
+            announcements: HashMap::new(),
+            commitments: HashMap::new(),
+            echoes: HashMap::new(),
+            nonce_shares: HashMap::new(),
+            partial_signatures: HashMap::new(),
+            equivocations: Vec::new(),
+        })
+    }
+
+    // The evidence of every equivocation detected so far
+    pub fn equivocations(&self) -> &[Equivocation] {
+        &self.equivocations
+    }
+
+    fn participant_index(&self) -> Identifier {
+        self.signer.participant_index
+    }
+
+    // Sign a payload for every peer with the identity key and the next sequence number
+    fn broadcast<R: Rng>(&mut self, payload: Payload, identity_key: &PrivateKey<TestnetV0>, rng: &mut R) -> Result<SignedEnvelope> {
+        let envelope = Envelope::new(&self.signer.session_id, Party::Participant(self.participant_index()), Party::Broadcast, self.next_sequence, payload)?;
+        self.next_sequence += 1;
+
+        SignedEnvelope::sign(&envelope, identity_key, rng)
+    }
+
+    // Broadcast this peer's commitment for the target
+    pub fn announce<R: Rng>(&mut self, identity_key: &PrivateKey<TestnetV0>, rng: &mut R) -> Result<SignedEnvelope> {
+        let commitment = match self.signer.announce()?.payload {
+            Payload::CommitmentAnnouncement(commitments) => commitments[0],
+            payload => bail!("Expected a commitment announcement, found a {}", payload.name()),
+        };
+        let payload = Payload::PeerAnnouncement(Box::new(PeerAnnouncement { target_hash: self.target_hash, commitment }));
+
+        let signed = self.broadcast(payload, identity_key, rng)?;
+        self.commitments.insert(self.participant_index(), commitment);
+        self.announcements.insert(self.participant_index(), signed.clone());
+
+        Ok(signed)
+    }
+
+    // Record that a participant signed two conflicting envelopes and return the error to report
+    fn equivocated(&mut self, participant_index: Identifier, first: SignedEnvelope, second: SignedEnvelope) -> Error {
+        self.equivocations.push(Equivocation { participant_index, first, second });
+        anyhow!("Participant {participant_index} equivocated: it signed two conflicting messages in session '{}'", self.signer.session_id)
+    }
+
+    // Compare an announcement echoed by a peer with the one received from its owner
+    fn check_echoed_announcement(&mut self, owner: Identifier, echoed: &SignedEnvelope) -> Result<()> {
+        let received = match self.announcements.get(&owner) {
+            Some(received) => received.clone(),
+            None => return Ok(()),
+        };
+        if Envelope::decode(received.envelope.as_bytes())?.payload != Envelope::decode(echoed.envelope.as_bytes())?.payload {
+            return Err(self.equivocated(owner, received, echoed.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Accept a peer's authenticated broadcast
+    ///
+    /// A retransmission of a message already received is ignored. A peer sending two different
+    /// messages with the same sequence number or of the same kind, or announcing different
+    /// commitments to different peers, is rejected as equivocating and both envelopes are kept.
+    /// Nonce shares are checked against the sender's announced commitment and public key share.
+    pub fn receive(&mut self, signed: &SignedEnvelope, config: &GroupConfig, account: &ThresholdAccount) -> Result<()> {
+        let envelope = signed.open(config)?;
+        let sender = match envelope.sender {
+            Party::Participant(sender) if sender != self.participant_index() && self.peers.contains(&sender) => sender,
+            sender => bail!("{sender} is not a peer of session '{}'", self.signer.session_id),
+        };
+        ensure!(envelope.recipient == Party::Broadcast, "Peers only accept broadcasts, found a message for {}", envelope.recipient);
+        ensure!(
+            envelope.session_id == self.signer.session_id,
+            "Received a {} for session '{}' in session '{}'", envelope.payload.name(), envelope.session_id, self.signer.session_id
+        );
+
+        // An earlier message with the same sequence number or of the same kind must be repeated as is
+        let received = self.received.get(&sender).map_or(&[][..], Vec::as_slice);
+        let previous = received
+            .iter()
+            .find(|(previous, _)| previous.sequence == envelope.sequence || previous.payload.name() == envelope.payload.name())
+            .cloned();
+        let last_sequence = received.last().map(|(last, _)| last.sequence);
+        if let Some((previous, previous_signed)) = previous {
+            if previous.payload != envelope.payload {
+                return Err(self.equivocated(sender, previous_signed, signed.clone()));
+            }
+            if previous.sequence == envelope.sequence {
+                return Ok(());
+            }
+        }
+        envelope.check_order(&self.signer.session_id, &[Party::Broadcast], last_sequence)?;
+
+        match &envelope.payload {
+            Payload::PeerAnnouncement(announcement) => {
+                ensure!(announcement.target_hash == self.target_hash, "Participant {sender} was asked to sign another target");
+                self.commitments.insert(sender, announcement.commitment);
+                self.announcements.insert(sender, signed.clone());
+
+                // Check the echoes received before the announcement
+                let echoed: Vec<SignedEnvelope> = self
+                    .echoes
+                    .values()
+                    .flatten()
+                    .filter(|echoed| matches!(Envelope::decode(echoed.envelope.as_bytes()), Ok(echoed) if echoed.sender == Party::Participant(sender)))
+                    .cloned()
+                    .collect();
+                for echoed in &echoed {
+                    self.check_echoed_announcement(sender, echoed)?;
+                }
+            }
+            Payload::CommitmentEcho(echo) => {
+                ensure!(echo.target_hash == self.target_hash, "Participant {sender} echoed the commitments for another target");
+                ensure!(
+                    echo.announcements.len() == self.peers.len(),
+                    "Participant {sender} echoed {} announcements, expected {}", echo.announcements.len(), self.peers.len()
+                );
+                // Every echoed announcement must be signed by its owner, in this session and in order
+                for (peer, echoed) in self.peers.iter().zip(&echo.announcements) {
+                    let announcement = echoed.open(config)?;
+                    ensure!(
+                        announcement.sender == Party::Participant(*peer)
+                            && announcement.session_id == self.signer.session_id
+                            && matches!(announcement.payload, Payload::PeerAnnouncement(..)),
+                        "Participant {sender} echoed an invalid announcement for participant {peer}"
+                    );
+                }
+                for (owner, echoed) in self.peers.clone().into_iter().zip(&echo.announcements) {
+                    self.check_echoed_announcement(owner, echoed)?;
+                }
+                self.echoes.insert(sender, echo.announcements.clone());
+            }
+            Payload::NonceShares(nonce_shares) => {
+                ensure!(nonce_shares.participant_index == sender, "Participant {sender} sent the nonce shares of participant {}", nonce_shares.participant_index);
+                let signing_commitment = match self.commitments.get(&sender) {
+                    Some(signing_commitment) => *signing_commitment,
+                    None => bail!("Participant {sender} sent nonce shares before its commitment"),
+                };
+                let public_key = match account.public_keys.public_keys.get(&sender) {
+                    Some(public_key) => public_key,
+                    None => bail!("Participant {sender} is not part of the group"),
+                };
+                nonce_shares.verify(&account.address()?, public_key, &signing_commitment)?;
+                self.nonce_shares.insert(sender, (**nonce_shares).clone());
+            }
+            Payload::PartialSignature(partial_signature) => {
+                self.partial_signatures.insert(sender, *partial_signature);
+            }
+            payload => bail!("Peers do not accept a {}", payload.name()),
+        }
+
+        self.received.entry(sender).or_default().push((envelope, signed.clone()));
+        Ok(())
+    }
+
+    // Every peer's commitment as received by this peer, ordered by identifier
+    fn commitment_view(&self) -> Result<Vec<SigningCommitment>> {
+        self.peers
+            .iter()
+            .map(|peer| match self.commitments.get(peer) {
+                Some(commitment) => Ok(*commitment),
+                None => bail!("Waiting for the commitment of participant {peer}"),
+            })
+            .collect()
+    }
+
+    // Echo every signed announcement this peer received
+    pub fn echo<R: Rng>(&mut self, identity_key: &PrivateKey<TestnetV0>, rng: &mut R) -> Result<SignedEnvelope> {
+        self.commitment_view()?;
+        let announcements = self.peers.iter().map(|peer| self.announcements[peer].clone()).collect();
+
+        let echo = CommitmentEcho { target_hash: self.target_hash, announcements };
+        self.broadcast(Payload::CommitmentEcho(Box::new(echo)), identity_key, rng)
+    }
+
+    /// Derive the package every peer signs once the echoes of all peers agree with this peer's view
+    ///
+    /// The echoed announcements are signed by their owners, so a disagreement names the owner
+    /// of the announcement, never the peer that echoed it. The whole announcement is compared,
+    /// so an owner announcing the same commitment for two targets is caught as well.
+    pub fn package(&self) -> Result<SigningRequest> {
+        let view = self.commitment_view()?;
+        for peer in self.peers.iter().filter(|peer| **peer != self.participant_index()) {
+            let echo = match self.echoes.get(peer) {
+                Some(echo) => echo,
+                None => bail!("Waiting for the echo of participant {peer}"),
+            };
+            for (owner, echoed) in self.peers.iter().zip(echo) {
+                let received = Envelope::decode(self.announcements[owner].envelope.as_bytes())?.payload;
+                ensure!(
+                    Envelope::decode(echoed.envelope.as_bytes())?.payload == received,
+                    "Participant {owner} equivocated: it announced a different commitment or target to participant {peer}"
+                );
+            }
+        }
+
+        let mut request = match self.target.clone() {
+            SigningTarget::Message(message) => SigningRequest::Message(SigningPackage::new(view, message)),
+            SigningTarget::Call { program_id, function_name, inputs, input_types } => {
+                SigningRequest::Request(RequestSigningPackage::new(program_id, function_name, inputs, input_types, view)?)
+            }
+        };
+        if let SigningRequest::Request(package) = &mut request {
+            for peer in &self.peers {
+                if let Some(nonce_shares) = self.nonce_shares.get(peer) {
+                    package.add_nonce_shares(nonce_shares.clone())?;
+                }
+            }
+        }
+
+        Ok(request)
+    }
+
+    // Broadcast this peer's nonce shares for the request
+    pub fn broadcast_nonce_shares<R: Rng>(
+        &mut self,
+        account: &ThresholdAccount,
+        share: &SignerShare,
+        identity_key: &PrivateKey<TestnetV0>,
+        rng: &mut R,
+    ) -> Result<SignedEnvelope> {
+        let package = match self.package()? {
+            SigningRequest::Request(package) => package,
+            SigningRequest::Message(..) => bail!("Session '{}' signs a message, which needs no nonce shares", self.signer.session_id),
+        };
+
+        let payload = self.signer.nonce_shares(&package, account, share, rng)?.payload;
+        if let Payload::NonceShares(nonce_shares) = &payload {
+            self.nonce_shares.insert(self.participant_index(), (**nonce_shares).clone());
+        }
+
+        self.broadcast(payload, identity_key, rng)
+    }
+
+    /// Sign the derived package, if this peer's policy allows it, and broadcast the partial signature
+    ///
+    /// Requests are only signed once every peer's nonce shares are in.
+    pub fn sign<R: Rng>(
+        &mut self,
+        account: &ThresholdAccount,
+        share: &SignerShare,
+        policy: &SigningPolicy,
+        ledger: &mut SpendingLedger,
+        identity_key: &PrivateKey<TestnetV0>,
+        rng: &mut R,
+    ) -> Result<SignedEnvelope> {
+        let request = self.package()?;
+        if let SigningRequest::Request(..) = &request {
+            for peer in &self.peers {
+                ensure!(self.nonce_shares.contains_key(peer), "Waiting for the nonce shares of participant {peer}");
+            }
+        }
+
+        let payload = self.signer.sign(&request, account, share, policy, ledger)?.payload;
+        if let Payload::PartialSignature(partial_signature) = &payload {
+            self.partial_signatures.insert(self.participant_index(), *partial_signature);
+        }
+
+        self.broadcast(payload, identity_key, rng)
+    }
+
+    // Aggregate the signature once every peer's partial signature is in
+    pub fn aggregate(&self, account: &ThresholdAccount) -> Result<AggregationResult> {
+        let mut partial_signatures = Vec::with_capacity(self.peers.len());
+        for peer in &self.peers {
+            match self.partial_signatures.get(peer) {
+                Some(partial_signature) => partial_signatures.push(*partial_signature),
+                None => bail!("Waiting for the partial signature of participant {peer}"),
+            }
+        }
+
+        match self.package()? {
+            SigningRequest::Message(package) => Ok(AggregationResult::Signature(package.aggregate(account, &partial_signatures)?)),
+            SigningRequest::Request(package) => Ok(AggregationResult::Request(Box::new(package.aggregate(account, &partial_signatures)?))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::generate_message::SigningMessage;
+
+    use snarkvm_console_account::Address;
+    use snarkvm_console_program::{Identifier as FunctionName, ProgramID, Value, ValueType};
+    use snarkvm_console_types::Group;
+    use snarkvm_console_types_scalar::TestRng;
+    use std::{path::PathBuf, str::FromStr};
+
+    struct Peer {
+        session: PeerSession,
+        share: SignerShare,
+        identity_key: PrivateKey<TestnetV0>,
+    }
+
+    fn temp_path(name: &str, rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("aleo-frost-peer-{name}-{}.json", rng.gen::<u64>()))
+    }
+
+    // Two peers of a 2-of-3 group signing the target, and the group config authenticating them
+    fn peers(target: &SigningTarget, rng: &mut TestRng) -> (ThresholdAccount, GroupConfig, Vec<Peer>) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+
+        let signers: Vec<Identifier> = shares[..2].iter().map(|share| share.participant_index).collect();
+        let mut participants = HashMap::new();
+        let mut peers = Vec::with_capacity(signers.len());
+        for share in &shares[..2] {
+            let identity_key = PrivateKey::new(rng).unwrap();
+            participants.insert(share.participant_index, Address::try_from(&identity_key).unwrap());
+            let path = temp_path("session", rng);
+            let session = PeerSession::new(path, "session-1", share.participant_index, signers.clone(), target.clone(), 600, rng).unwrap();
+            peers.push(Peer { session, share: share.clone(), identity_key });
+        }
+        let config = GroupConfig { coordinator: Address::try_from(&PrivateKey::new(rng).unwrap()).unwrap(), participants };
+
+        (account, config, peers)
+    }
+
+    // Deliver every peer's broadcast to the other peers, twice to check that retransmissions are ignored
+    fn deliver(peers: &mut [Peer], broadcasts: &[SignedEnvelope], config: &GroupConfig, account: &ThresholdAccount) {
+        for (index, peer) in peers.iter_mut().enumerate() {
+            for (sender, broadcast) in broadcasts.iter().enumerate() {
+                if sender != index {
+                    peer.session.receive(broadcast, config, account).unwrap();
+                    peer.session.receive(broadcast, config, account).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_peers_sign_a_message() {
+        let rng = &mut TestRng::default();
+        let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+        let (account, config, mut peers) = peers(&SigningTarget::Message(message.clone()), rng);
+
+        let announcements: Vec<SignedEnvelope> = peers.iter_mut().map(|peer| peer.session.announce(&peer.identity_key, rng).unwrap()).collect();
+        deliver(&mut peers, &announcements, &config, &account);
+        let echoes: Vec<SignedEnvelope> = peers.iter_mut().map(|peer| peer.session.echo(&peer.identity_key, rng).unwrap()).collect();
+        deliver(&mut peers, &echoes, &config, &account);
+
+        let mut partial_signatures = Vec::with_capacity(peers.len());
+        for peer in &mut peers {
+            let mut ledger = SpendingLedger::open(temp_path("spending", rng)).unwrap();
+            let signed = peer.session.sign(&account, &peer.share, &SigningPolicy::default(), &mut ledger, &peer.identity_key, rng).unwrap();
+            partial_signatures.push(signed);
+        }
+        deliver(&mut peers, &partial_signatures, &config, &account);
+
+        for peer in &peers {
+            assert!(peer.session.equivocations().is_empty());
+            match peer.session.aggregate(&account).unwrap() {
+                AggregationResult::Signature(signature) => {
+                    assert!(signature.verify(&account.address().unwrap(), &message.to_fields().unwrap()))
+                }
+                result => panic!("Expected a signature, found {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_equivocation_is_kept_as_evidence() {
+        let rng = &mut TestRng::default();
+        let target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 10u64 }").unwrap());
+        let (account, config, mut peers) = peers(&target, rng);
+
+        let sender = &mut peers[0];
+        let participant_index = sender.share.participant_index;
+        let announcement = sender.session.announce(&sender.identity_key, rng).unwrap();
+
+        // The same peer signs a second announcement with another commitment
+        let (_, commitments) = preprocess(1, participant_index, rng);
+        let payload = Payload::PeerAnnouncement(Box::new(PeerAnnouncement { target_hash: target.hash().unwrap(), commitment: commitments[0] }));
+        let envelope = Envelope::new("session-1", Party::Participant(participant_index), Party::Broadcast, 0, payload).unwrap();
+        let conflicting = SignedEnvelope::sign(&envelope, &sender.identity_key, rng).unwrap();
+
+        let receiver = &mut peers[1].session;
+        receiver.receive(&announcement, &config, &account).unwrap();
+        assert!(receiver.receive(&conflicting, &config, &account).is_err());
+
+        // Both announcements are kept and anyone with the group config can check them
+        let equivocations = receiver.equivocations();
+        assert_eq!(equivocations.len(), 1);
+        assert_eq!(equivocations[0].participant_index, participant_index);
+        assert_eq!(equivocations[0].first, announcement);
+        assert_eq!(equivocations[0].second, conflicting);
+        equivocations[0].verify(&config).unwrap();
+    }
+
+    #[test]
+    fn test_announcement_for_another_target_is_refused() {
+        let rng = &mut TestRng::default();
+        let target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 10u64 }").unwrap());
+        let (account, config, mut peers) = peers(&target, rng);
+
+        let sender = &mut peers[0];
+        let announcement = sender.session.announce(&sender.identity_key, rng).unwrap();
+
+        // A peer asked to sign another amount refuses the announcement
+        let signers = vec![peers[0].share.participant_index, peers[1].share.participant_index];
+        let other_target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 1000u64 }").unwrap());
+        let mut other = PeerSession::new(temp_path("session", rng), "session-1", signers[1], signers, other_target, 600, rng).unwrap();
+        assert!(other.receive(&announcement, &config, &account).is_err());
+        assert!(peers[1].session.receive(&announcement, &config, &account).is_ok());
+    }
+
+    #[test]
+    fn test_bad_nonce_shares_are_refused() {
+        let rng = &mut TestRng::default();
+        let recipient = Address::try_from(&PrivateKey::<TestnetV0>::new(rng).unwrap()).unwrap();
+        let target = SigningTarget::Call {
+            program_id: ProgramID::from_str("credits.aleo").unwrap(),
+            function_name: FunctionName::from_str("transfer_public").unwrap(),
+            inputs: vec![Value::from_str(&recipient.to_string()).unwrap(), Value::from_str("10u64").unwrap()],
+            input_types: vec![ValueType::from_str("address.public").unwrap(), ValueType::from_str("u64.public").unwrap()],
+        };
+        let (account, config, mut peers) = peers(&target, rng);
+
+        let announcements: Vec<SignedEnvelope> = peers.iter_mut().map(|peer| peer.session.announce(&peer.identity_key, rng).unwrap()).collect();
+        deliver(&mut peers, &announcements, &config, &account);
+        let echoes: Vec<SignedEnvelope> = peers.iter_mut().map(|peer| peer.session.echo(&peer.identity_key, rng).unwrap()).collect();
+        deliver(&mut peers, &echoes, &config, &account);
+
+        let sender = &mut peers[0];
+        let signed = sender.session.broadcast_nonce_shares(&account, &sender.share, &sender.identity_key, rng).unwrap();
+
+        // The same nonce shares with a share that does not match the sender's commitment
+        let mut envelope = Envelope::decode(signed.envelope.as_bytes()).unwrap();
+        if let Payload::NonceShares(nonce_shares) = &mut envelope.payload {
+            nonce_shares.hiding = nonce_shares.hiding + Group::<TestnetV0>::generator();
+        }
+        let tampered = SignedEnvelope::sign(&envelope, &sender.identity_key, rng).unwrap();
+
+        let receiver = &mut peers[1].session;
+        assert!(receiver.receive(&tampered, &config, &account).is_err());
+        receiver.receive(&signed, &config, &account).unwrap();
+    }
+
+    #[test]
+    fn test_commitment_announced_for_two_targets_is_caught() {
+        let rng = &mut TestRng::default();
+        let target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 10u64 }").unwrap());
+        let (account, config, mut peers) = peers(&target, rng);
+
+        let announcements: Vec<SignedEnvelope> = peers.iter_mut().map(|peer| peer.session.announce(&peer.identity_key, rng).unwrap()).collect();
+        deliver(&mut peers, &announcements, &config, &account);
+
+        // The first peer echoes its own commitment announced for another target
+        let sender = &peers[0];
+        let participant_index = sender.share.participant_index;
+        let commitment = match Envelope::decode(announcements[0].envelope.as_bytes()).unwrap().payload {
+            Payload::PeerAnnouncement(announcement) => announcement.commitment,
+            payload => panic!("Expected an announcement, found a {}", payload.name()),
+        };
+        let other_target = SigningTarget::Message(SigningMessage::parse_value("{ amount: 1000u64 }").unwrap());
+        let payload = Payload::PeerAnnouncement(Box::new(PeerAnnouncement { target_hash: other_target.hash().unwrap(), commitment }));
+        let envelope = Envelope::new("session-1", Party::Participant(participant_index), Party::Broadcast, 0, payload).unwrap();
+        let other_announcement = SignedEnvelope::sign(&envelope, &sender.identity_key, rng).unwrap();
+
+        let echo = CommitmentEcho { target_hash: target.hash().unwrap(), announcements: vec![other_announcement.clone(), announcements[1].clone()] };
+        let envelope = Envelope::new("session-1", Party::Participant(participant_index), Party::Broadcast, 1, Payload::CommitmentEcho(Box::new(echo))).unwrap();
+        let signed = SignedEnvelope::sign(&envelope, &sender.identity_key, rng).unwrap();
+
+        let receiver = &mut peers[1].session;
+        assert!(receiver.receive(&signed, &config, &account).is_err());
+        assert_eq!(receiver.equivocations().len(), 1);
+        assert_eq!(receiver.equivocations()[0].first, announcements[0]);
+        assert_eq!(receiver.equivocations()[0].second, other_announcement);
+        receiver.equivocations()[0].verify(&config).unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+use std::{path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use snarkvm_console_account::Address;
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::{Ciphertext, Literal, Plaintext, ProgramID, Record, Value};
+use snarkvm_console_types::Group;
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{account::*, decryption::DecryptionShare, deployment::DeploymentSigningPackage, frost::*, generate_message::*, keys::*, preprocess::*, request::RequestSigningPackage, spending::*, summary::*};
+
+/// The number of seconds in a day
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// A signer's own rules for what it is willing to sign, read from a JSON file
+///
+/// Every rule is optional: a missing list allows everything, an empty list allows nothing.
+/// Bytes and fields messages are the exception, they are only allowed if listed in
+/// `allowed_message_kinds`, and record decryption shares are only produced if
+/// `allow_decryption` is set.
+///
+/// ```json
+/// {
+///   "allowed_programs": ["credits.aleo"],
+///   "allowed_functions": ["credits.aleo/transfer_public", "credits.aleo/fee_public"],
+///   "max_u64_amount": 10000000,
+///   "allowed_recipients": ["aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew"],
+///   "time_windows": [{ "start_hour": 9, "end_hour": 17 }],
+///   "allowed_message_kinds": ["Plaintext", "Bytes"],
+///   "spending_limits": [{ "program_id": "credits.aleo", "window_seconds": 86400, "max_amount": 10000000000 }]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningPolicy {
+    // The programs the signer may call or deploy
+    #[serde(default)]
+    pub allowed_programs: Option<Vec<ProgramID<TestnetV0>>>,
+    // The functions the signer may call, as "program/function"
+    #[serde(default)]
+    pub allowed_functions: Option<Vec<String>>,
+    // The largest u64 literal in a call or message, also applied to credits transfers and fees
+    #[serde(default)]
+    pub max_u64_amount: Option<u64>,
+    // The largest u128 literal in a call or message
+    #[serde(default)]
+    pub max_u128_amount: Option<u128>,
+    // The addresses the signer may send credits to or pass as call inputs
+    #[serde(default)]
+    pub allowed_recipients: Option<Vec<Address<TestnetV0>>>,
+    // The UTC hours of the day the signer may sign in, any time if empty
+    #[serde(default)]
+    pub time_windows: Vec<TimeWindow>,
+    // The kinds of messages the signer may sign, every kind but Bytes and Fields if missing
+    #[serde(default)]
+    pub allowed_message_kinds: Option<Vec<MessageKind>>,
+    // The rolling limits on the amounts approved, tracked in the signer's `SpendingLedger`
+    #[serde(default)]
+    pub spending_limits: Vec<SpendingLimit>,
+    // Whether the signer may produce decryption shares for the account's records
+    #[serde(default)]
+    pub allow_decryption: bool,
+}
+
+/// A range of UTC hours [start_hour, end_hour), wrapping past midnight if start_hour > end_hour
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeWindow {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+impl TimeWindow {
+    // Check whether the unix timestamp falls in the window
+    pub fn contains(&self, timestamp: u64) -> bool {
+        let hour = (timestamp % SECONDS_PER_DAY / 3600) as u8;
+
+        match self.start_hour <= self.end_hour {
+            true => self.start_hour <= hour && hour < self.end_hour,
+            false => self.start_hour <= hour || hour < self.end_hour,
+        }
+    }
+}
+
+impl SigningPolicy {
+    // Read a policy from a JSON file on disk, rejecting unknown rules
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let policy: Self = serde_json::from_str(&contents)?;
+        policy.validate()?;
+
+        Ok(policy)
+    }
+
+    // Write the policy to disk as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // Check that the time windows are hours of the day
+    pub fn validate(&self) -> Result<()> {
+        for window in &self.time_windows {
+            ensure!(window.start_hour < 24 && window.end_hour <= 24, "Invalid time window {}-{}", window.start_hour, window.end_hour);
+        }
+
+        Ok(())
+    }
+
+    /// Collect the reasons the policy refuses a summary at the given unix timestamp
+    ///
+    /// The summary is allowed if there are none.
+    pub fn violations(&self, summary: &SigningSummary, timestamp: u64) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        if !self.time_windows.is_empty() && !self.time_windows.iter().any(|window| window.contains(timestamp)) {
+            violations.push(format!("signing is not allowed at hour {} UTC", timestamp % SECONDS_PER_DAY / 3600));
+        }
+
+        let mut literals = Vec::new();
+        match summary {
+            SigningSummary::Call(call) => {
+                if let Some(programs) = &self.allowed_programs {
+                    if !programs.contains(&call.program_id) {
+                        violations.push(format!("program '{}' is not allowed", call.program_id));
+                    }
+                }
+                let function = format!("{}/{}", call.program_id, call.function_name);
+                if let Some(functions) = &self.allowed_functions {
+                    if !functions.contains(&function) {
+                        violations.push(format!("function '{function}' is not allowed"));
+                    }
+                }
+
+                if let Some(transfer) = &call.transfer {
+                    if let Some(max) = self.max_u64_amount {
+                        if transfer.amount > max {
+                            violations.push(format!("{function} moves {} microcredits, above the limit of {max}", transfer.amount));
+                        }
+                    }
+                }
+
+                for (_, input) in &call.inputs {
+                    if let Value::Plaintext(plaintext) = input {
+                        collect_literals(plaintext, &mut literals);
+                    }
+                }
+
+                // Every address passed to a call is a potential recipient
+                if let Some(recipients) = &self.allowed_recipients {
+                    for literal in &literals {
+                        if let Literal::Address(address) = literal {
+                            if !recipients.contains(address) {
+                                violations.push(format!("recipient '{address}' is not allowed"));
+                            }
+                        }
+                    }
+                }
+            }
+            SigningSummary::Message(message) => {
+                let allowed = match &self.allowed_message_kinds {
+                    Some(kinds) => kinds.contains(&message.kind),
+                    None => !message.kind.is_raw(),
+                };
+                if !allowed {
+                    violations.push(format!("{:?} messages are not allowed", message.kind));
+                }
+
+                match &message.message {
+                    SigningMessage::Value(Value::Plaintext(plaintext)) | SigningMessage::Plaintext(plaintext) => {
+                        collect_literals(plaintext, &mut literals)
+                    }
+                    SigningMessage::Literal(literal) => literals.push(literal.clone()),
+                    // Opaque messages hold no literals to check, so they cannot pass an amount limit
+                    SigningMessage::Bytes(..) | SigningMessage::Fields(..) => {
+                        if self.max_u64_amount.is_some() || self.max_u128_amount.is_some() {
+                            violations.push(format!("{:?} messages cannot be checked against the amount limits", message.kind));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            SigningSummary::Deployment(deployment) => {
+                if let Some(programs) = &self.allowed_programs {
+                    if !programs.contains(&deployment.program_id) {
+                        violations.push(format!("program '{}' is not allowed", deployment.program_id));
+                    }
+                }
+            }
+        }
+
+        for literal in &literals {
+            match literal {
+                Literal::U64(amount) => {
+                    if let Some(max) = self.max_u64_amount {
+                        if **amount > max {
+                            violations.push(format!("amount {literal} is above the limit of {max}u64"));
+                        }
+                    }
+                }
+                Literal::U128(amount) => {
+                    if let Some(max) = self.max_u128_amount {
+                        if **amount > max {
+                            violations.push(format!("amount {literal} is above the limit of {max}u128"));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        violations
+    }
+
+    /// Evaluate the policy on a signing package, returning its summary if the package is allowed
+    ///
+    /// A package that violates the policy or would exceed a spending limit is refused with every
+    /// reason it was refused for. An allowed package is recorded in the spending ledger.
+    pub fn approve<S: Summarize>(&self, package: &S, ledger: &mut SpendingLedger, timestamp: u64) -> Result<SigningSummary> {
+        let summary = package.summary()?;
+
+        let mut violations = self.violations(&summary, timestamp);
+        violations.extend(ledger.violations(&self.spending_limits, &summary, timestamp));
+        if !violations.is_empty() {
+            bail!("Refusing to sign '{}': {}", summary, violations.join("; "));
+        }
+        ledger.record(&self.spending_limits, &summary, timestamp)?;
+
+        Ok(summary)
+    }
+
+    // Generate a partial signature on a message package if the policy allows it now
+    pub fn partial_sign(
+        &self,
+        package: &SigningPackage,
+        ledger: &mut SpendingLedger,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        self.approve(package, ledger, current_timestamp()?)?;
+        package.partial_sign_approved(participant_signing_share, signing_nonce, pr_sig)
+    }
+
+    // Generate a partial signature on a request package if the policy allows it now
+    pub fn partial_sign_request(
+        &self,
+        package: &RequestSigningPackage,
+        ledger: &mut SpendingLedger,
+        account: &ThresholdAccount,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+    ) -> Result<PartialThresholdSignature> {
+        self.approve(package, ledger, current_timestamp()?)?;
+        package.partial_sign(account, participant_signing_share, signing_nonce)
+    }
+
+    // Generate a partial signature as owner of a deployment if the policy allows it now
+    pub fn partial_sign_deployment(
+        &self,
+        package: &DeploymentSigningPackage,
+        ledger: &mut SpendingLedger,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        pr_sig: Group<TestnetV0>,
+    ) -> Result<PartialThresholdSignature> {
+        self.approve(package, ledger, current_timestamp()?)?;
+        package.partial_sign(participant_signing_share, signing_nonce, pr_sig)
+    }
+
+    /// Generate a decryption share for a record of the account if the policy allows it now
+    ///
+    /// Combined with the view key terms, the shares let anyone read the record, so they are
+    /// refused unless `allow_decryption` is set and the time windows allow signing.
+    pub fn decryption_share<R: Rng>(
+        &self,
+        record: &Record<TestnetV0, Ciphertext<TestnetV0>>,
+        participant_signing_share: &SignerShare,
+        rng: &mut R,
+    ) -> Result<DecryptionShare> {
+        let timestamp = current_timestamp()?;
+        ensure!(self.allow_decryption, "Refusing to decrypt record {}: decryption is not allowed", record.nonce());
+        ensure!(
+            self.time_windows.is_empty() || self.time_windows.iter().any(|window| window.contains(timestamp)),
+            "Refusing to decrypt record {}: signing is not allowed at hour {} UTC", record.nonce(), timestamp % SECONDS_PER_DAY / 3600
+        );
+
+        DecryptionShare::new(participant_signing_share, record, rng)
+    }
+}
+
+// Collect the literals of a plaintext, descending into structs and arrays
+pub(crate) fn collect_literals(plaintext: &Plaintext<TestnetV0>, literals: &mut Vec<Literal<TestnetV0>>) {
+    match plaintext {
+        Plaintext::Literal(literal, ..) => literals.push(literal.clone()),
+        Plaintext::Struct(members, ..) => members.values().for_each(|member| collect_literals(member, literals)),
+        Plaintext::Array(elements, ..) => elements.iter().for_each(|element| collect_literals(element, literals)),
+    }
+}
+
+// The current unix timestamp in seconds
+pub(crate) fn current_timestamp() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
@@ -3,9 +3,13 @@ use snarkvm_console_types::{Group, Scalar};
 use snarkvm_console_types_scalar::Uniform;
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::identifier::Identifier;
 
 // The hiding and binding nonces used (only once) for signing operation
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SigningNonce {
     // d\_{ij}
     pub(crate) hiding: Scalar<TestnetV0>,
@@ -22,10 +26,11 @@ impl SigningNonce {
 }
 
 // A precomputed commitment share
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SigningCommitment {
-    // The index of the participant.
-    pub(crate) participant_index: u64,
+    // The identifier of the participant.
+    pub(crate) participant_index: Identifier,
     // The hiding commitment - D\_{ij}
     pub(crate) hiding: Group<TestnetV0>,
     // The binding commitment - E\_{ij}
@@ -33,9 +38,9 @@ pub struct SigningCommitment {
 }
 
 impl SigningCommitment {
-    // Generate the commitment share for a given participant index using a provided nonce
+    // Generate the commitment share for a given participant identifier using a provided nonce
     pub fn from(
-        participant_index: u64,
+        participant_index: Identifier,
         nonce: &SigningNonce
     ) -> Self {
         Self {
@@ -54,7 +59,7 @@ impl SigningCommitment {
 /// SigningNonce should be kept secret, while SigningCommitment should be distributed to other participants
 pub fn preprocess<R: Rng> (
     num_nonces: usize,
-    participant_index: u64,
+    participant_index: Identifier,
     rng: &mut R,
 ) -> (Vec<SigningNonce>, Vec<SigningCommitment>) {
     let mut signing_nonces = Vec::with_capacity(num_nonces);
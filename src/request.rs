@@ -0,0 +1,525 @@
+use std::collections::HashMap;
+
+use snarkvm_console_account::ToFields;
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_program::{Identifier as FunctionName, InputID, Plaintext, ProgramID, Record, Request, Value, ValueType};
+use snarkvm_console_types::{Field, Scalar, U16};
+use snarkvm_console_types_scalar::{anyhow, bail, ensure, Result, ToBits};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{account::*, frost::*, gamma::*, generate_message::bytes_to_fields, identifier::Identifier, keys::*, preprocess::*, summary::Summarize, utils::*};
+
+/// Domain separator for the binding values of request packages
+const REQUEST_BINDING_DOMAIN: &str = "AleoFrostRequest";
+
+/// Check whether a message has the shape of a request's signed message [tvk, tcm, function id, ...]
+///
+/// An Aleo signature on such a message is also a valid signature of the request it encodes, since
+/// the request challenge is H(R, pk_sig, pr_sig, address, [tvk, tcm, ...]) with tcm = Hash(tvk).
+/// Message packages whose fields pass this check are never signed.
+pub fn is_request_message(message: &[Field<TestnetV0>]) -> Result<bool> {
+    match message {
+        [tvk, tcm, _, ..] => Ok(TestnetV0::hash_psd2(&[*tvk])? == *tcm),
+        _ => Ok(false),
+    }
+}
+
+/// A request to call an Aleo function from the threshold account, with the signers' commitments
+///
+/// Signing follows `Request::sign` with the transition secret key r replaced by the FROST nonces:
+/// - The binding values bind the commitments to the function id and the inputs
+/// - r * G is the group commitment R = Sum (D_i + rho_i * E_i)
+/// - tvk = (r * address).x, combined from the signers' `NonceShares` without the view key
+/// - c = H(R, pk_sig, pr_sig, address, [tvk, tcm, function id, input ids])
+///
+/// Record inputs additionally need gamma = H^sk_sig and r * H, combined from the signers' `GammaShare`s.
+/// Every signer's nonce shares are added to the package before anyone signs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestSigningPackage {
+    // The program of the function call
+    pub program_id: ProgramID<TestnetV0>,
+    // The function called
+    pub function_name: FunctionName<TestnetV0>,
+    // The function inputs
+    pub inputs: Vec<Value<TestnetV0>>,
+    // The type of every function input
+    pub input_types: Vec<ValueType<TestnetV0>>,
+    // Each participant's public signing commitment
+    pub signing_commitments: Vec<SigningCommitment>,
+    // Each participant's nonce shares for the transition view key and the record inputs
+    #[serde(default)]
+    pub nonce_shares: Vec<NonceShares>,
+}
+
+// The request data every signer derives from a signing package
+struct PreparedRequest {
+    binding_values: HashMap<Identifier, Scalar<TestnetV0>>,
+    input_ids: Vec<InputID<TestnetV0>>,
+    challenge: Scalar<TestnetV0>,
+    sk_tag: Field<TestnetV0>,
+    tvk: Field<TestnetV0>,
+    tcm: Field<TestnetV0>,
+}
+
+impl RequestSigningPackage {
+    pub fn new(
+        program_id: ProgramID<TestnetV0>,
+        function_name: FunctionName<TestnetV0>,
+        inputs: Vec<Value<TestnetV0>>,
+        input_types: Vec<ValueType<TestnetV0>>,
+        signing_commitments: Vec<SigningCommitment>,
+    ) -> Result<Self> {
+        ensure!(
+            inputs.len() == input_types.len(),
+            "Function '{program_id}/{function_name}' expects {} inputs, found {}", input_types.len(), inputs.len()
+        );
+
+        Ok(Self { program_id, function_name, inputs, input_types, signing_commitments, nonce_shares: Vec::new() })
+    }
+
+    // The commitments of the records spent by the request
+    pub fn record_commitments(&self) -> Result<Vec<Field<TestnetV0>>> {
+        let mut commitments = Vec::new();
+        for (input, input_type) in self.inputs.iter().zip(&self.input_types) {
+            if let ValueType::Record(record_name) = input_type {
+                match input {
+                    Value::Record(record) => commitments.push(record.to_commitment(&self.program_id, record_name)?),
+                    _ => bail!("Expected a record input, found a plaintext input"),
+                }
+            }
+        }
+
+        Ok(commitments)
+    }
+
+    /// Generate the participant's nonce shares for the request
+    ///
+    /// The nonce shares of every signer are added to the package with `add_nonce_shares`
+    /// before partial signing; they carry a gamma share for every record spent.
+    pub fn nonce_shares_for<R: Rng>(
+        &self,
+        account: &ThresholdAccount,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+        rng: &mut R,
+    ) -> Result<NonceShares> {
+        NonceShares::new(participant_signing_share, signing_nonce, &account.address()?, &self.record_commitments()?, rng)
+    }
+
+    // Add the nonce shares of a participant to the package, refusing a second, different set
+    pub fn add_nonce_shares(&mut self, nonce_shares: NonceShares) -> Result<()> {
+        match self.nonce_shares.iter().find(|existing| existing.participant_index == nonce_shares.participant_index) {
+            Some(existing) => ensure!(
+                *existing == nonce_shares,
+                "Participant {} sent two different sets of nonce shares", nonce_shares.participant_index
+            ),
+            None => self.nonce_shares.push(nonce_shares),
+        }
+
+        Ok(())
+    }
+
+    // Check that every signer's nonce shares are in the package
+    pub fn has_nonce_shares(&self) -> bool {
+        self.signing_commitments
+            .iter()
+            .all(|commitment| self.nonce_shares.iter().any(|shares| shares.participant_index == commitment.participant_index))
+    }
+
+    // Compute the function id as Hash(network_id, program_id, function_name)
+    pub fn function_id(&self) -> Result<Field<TestnetV0>> {
+        TestnetV0::hash_bhp1024(
+            &(U16::<TestnetV0>::new(TestnetV0::ID), self.program_id.name(), self.program_id.network(), self.function_name).to_bits_le(),
+        )
+    }
+
+    /// The message the binding values are computed over
+    ///
+    /// [request domain, function id, (input type, input)...] -- the request's challenge message
+    /// depends on R through tvk, so the commitments are bound to the call itself instead.
+    pub fn binding_message(&self) -> Result<Vec<Field<TestnetV0>>> {
+        let mut message = vec![Field::<TestnetV0>::new_domain_separator(REQUEST_BINDING_DOMAIN), self.function_id()?];
+        for (input, input_type) in self.inputs.iter().zip(&self.input_types) {
+            message.extend(bytes_to_fields(input_type.to_string().as_bytes())?);
+            message.extend(input.to_fields()?);
+        }
+
+        Ok(message)
+    }
+
+    // Derive the group commitment, tvk, input ids and challenge of the request
+    fn prepare(&self, account: &ThresholdAccount) -> Result<PreparedRequest> {
+        let binding_message = self.binding_message()?;
+
+        // Calculating rho_i in order to calculate R
+        let mut binding_values: HashMap<Identifier, Scalar<TestnetV0>> = HashMap::with_capacity(self.signing_commitments.len());
+        for commitment in &self.signing_commitments {
+            let rho_i = calculate_binding_value(commitment.participant_index, &self.signing_commitments, &binding_message);
+            binding_values.insert(commitment.participant_index, rho_i);
+        }
+
+        // Calculate the group commitment R = r * G, the transition public key
+        let group_commitment = calculate_group_commitment(&self.signing_commitments, &binding_values);
+
+        // Compute the transition view key tvk as r * caller, combined from the signers' nonce shares
+        let address = account.address()?;
+        let r_address = combine_transition_key_shares(
+            &address,
+            &self.nonce_shares,
+            &self.signing_commitments,
+            &binding_values,
+            &account.public_keys,
+        )?;
+        let tvk = r_address.to_x_coordinate();
+        // Compute the transition commitment tcm as Hash(tvk)
+        let tcm = TestnetV0::hash_psd2(&[tvk])?;
+        let sk_tag = account.sk_tag;
+        let gamma_shares: Vec<GammaShare> = self.nonce_shares.iter().flat_map(|shares| shares.gamma_shares.iter().copied()).collect();
+
+        let function_id = self.function_id()?;
+
+        // Construct the signed message as [tvk, tcm, function ID, input IDs]
+        let mut message = Vec::with_capacity(3 + 2 * self.inputs.len());
+        message.extend([tvk, tcm, function_id]);
+
+        let mut input_ids = Vec::with_capacity(self.inputs.len());
+        for (index, (input, input_type)) in self.inputs.iter().zip(&self.input_types).enumerate() {
+            // Construct the (console) input index as a field element.
+            let index = Field::<TestnetV0>::from_u16(u16::try_from(index)?);
+
+            match input_type {
+                // A constant or public input is hashed (using tcm) to a field element.
+                ValueType::Constant(..) | ValueType::Public(..) => {
+                    ensure!(matches!(input, Value::Plaintext(..)), "Expected a plaintext input");
+
+                    // Construct the preimage as (function ID || input || tcm || index)
+                    let mut preimage = vec![function_id];
+                    preimage.extend(input.to_fields()?);
+                    preimage.push(tcm);
+                    preimage.push(index);
+                    let input_hash = TestnetV0::hash_psd8(&preimage)?;
+
+                    message.push(input_hash);
+                    match input_type {
+                        ValueType::Constant(..) => input_ids.push(InputID::Constant(input_hash)),
+                        _ => input_ids.push(InputID::Public(input_hash)),
+                    }
+                }
+                // A private input is encrypted (using tvk) and hashed to a field element.
+                ValueType::Private(..) => {
+                    // Compute the input view key as Hash(function ID || tvk || index)
+                    let input_view_key = TestnetV0::hash_psd4(&[function_id, tvk, index])?;
+                    let ciphertext = match input {
+                        Value::Plaintext(plaintext) => plaintext.encrypt_symmetric(input_view_key)?,
+                        _ => bail!("Expected a plaintext input, found a record input"),
+                    };
+                    let input_hash = TestnetV0::hash_psd8(&ciphertext.to_fields()?)?;
+
+                    message.push(input_hash);
+                    input_ids.push(InputID::Private(input_hash));
+                }
+                // An external record input is hashed (using tvk) to a field element.
+                ValueType::ExternalRecord(..) => {
+                    // Construct the preimage as (function ID || input || tvk || index)
+                    let mut preimage = vec![function_id];
+                    preimage.extend(input.to_fields()?);
+                    preimage.push(tvk);
+                    preimage.push(index);
+                    let input_hash = TestnetV0::hash_psd8(&preimage)?;
+
+                    message.push(input_hash);
+                    input_ids.push(InputID::ExternalRecord(input_hash));
+                }
+                // A record input is spent using the gamma combined from the signers' gamma shares.
+                ValueType::Record(record_name) => {
+                    let record = match input {
+                        Value::Record(record) => record,
+                        _ => bail!("Expected a record input, found a plaintext input"),
+                    };
+                    ensure!(**record.owner() == address, "Input record for '{}' must belong to the signer", self.program_id);
+
+                    // Compute the record commitment and the generator H
+                    let commitment = record.to_commitment(&self.program_id, record_name)?;
+                    let h = record_generator(commitment)?;
+
+                    // Combine gamma = H^sk_sig and r * H from the signers' gamma shares
+                    let (gamma, h_r) = combine_gamma_shares(
+                        commitment,
+                        &gamma_shares,
+                        &self.signing_commitments,
+                        &binding_values,
+                        &account.public_keys,
+                    )?;
+
+                    // Compute the serial number from gamma and the tag from sk_tag
+                    let serial_number = Record::<TestnetV0, Plaintext<TestnetV0>>::serial_number_from_gamma(&gamma, commitment)?;
+                    let tag = Record::<TestnetV0, Plaintext<TestnetV0>>::tag(sk_tag, commitment)?;
+
+                    // Add (H, r * H, gamma, tag) to the message
+                    message.extend([h, h_r, gamma].iter().map(|point| point.to_x_coordinate()));
+                    message.push(tag);
+                    input_ids.push(InputID::Record(commitment, gamma, serial_number, tag));
+                }
+                _ => bail!("Input type '{input_type}' is not supported by threshold request signing"),
+            }
+        }
+
+        // Compute the challenge as HashToScalar(R, pk_sig, pr_sig, signer, [tvk, tcm, function ID, input IDs])
+        let challenge = calculate_challenge(group_commitment, account.group_public_key(), account.pr_sig, &message)?;
+
+        Ok(PreparedRequest { binding_values, input_ids, challenge, sk_tag, tvk, tcm })
+    }
+
+    /// Generate a participant's partial signature over the request
+    ///
+    /// z_i = d_i + (e_i * rho_i) - lambda_i * s_i * c
+    ///
+    /// Requests without a clear-signing summary are refused.
+    pub fn partial_sign(
+        &self,
+        account: &ThresholdAccount,
+        participant_signing_share: &SignerShare,
+        signing_nonce: &SigningNonce,
+    ) -> Result<PartialThresholdSignature> {
+        self.summary()?;
+        let prepared = self.prepare(account)?;
+
+        let signer_binding_value = prepared
+            .binding_values
+            .get(&participant_signing_share.participant_index)
+            .ok_or_else(|| anyhow!("Missing binding value"))?;
+
+        // Calculate the Lagrange coefficient
+        let participant_indexes: Vec<Identifier> = self.signing_commitments.iter().map(|commitment| commitment.participant_index).collect();
+        let lambda_i = calculate_lagrange_coefficients(participant_signing_share.participant_index, &participant_indexes, None)?;
+
+        Ok(PartialThresholdSignature::from_challenge(
+            participant_signing_share.participant_index,
+            lambda_i * participant_signing_share.secret_key.0,
+            signing_nonce,
+            *signer_binding_value,
+            prepared.challenge,
+        ))
+    }
+
+    /// Aggregate the partial signatures into a `Request` signed by the threshold account
+    ///
+    /// Every partial signature is checked against its signer's public key share first, and the
+    /// request is checked with `Request::verify` before it is returned.
+    pub fn aggregate(
+        &self,
+        account: &ThresholdAccount,
+        partial_signatures: &[PartialThresholdSignature],
+    ) -> Result<Request<TestnetV0>> {
+        let prepared = self.prepare(account)?;
+        verify_partial_signatures(
+            partial_signatures,
+            &self.signing_commitments,
+            &prepared.binding_values,
+            prepared.challenge,
+            &account.public_keys,
+        )?;
+        let signature = aggregate_signature(prepared.challenge, partial_signatures, account.compute_key()?);
+
+        let request = Request::from((
+            account.address()?,
+            U16::new(TestnetV0::ID),
+            self.program_id,
+            self.function_name,
+            prepared.input_ids,
+            self.inputs.clone(),
+            signature,
+            prepared.sk_tag,
+            prepared.tvk,
+            prepared.tcm,
+        ));
+
+        ensure!(request.verify(&self.input_types), "The threshold signed request failed to verify");
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{generate_message::*, policy::SigningPolicy, summary::*};
+
+    use snarkvm_console_account::PrivateKey;
+    use snarkvm_console_types_scalar::TestRng;
+    use std::str::FromStr;
+
+    const RECIPIENT: &str = "aleo1hy0uyudcr24q8nmxr8nlk82penl8jtqyfyuyz6mr5udlt0g3vyfqt9l7ew";
+
+    // A 2-of-3 threshold account dealt from a fresh private key
+    fn threshold_account(rng: &mut TestRng) -> (ThresholdAccount, Vec<SignerShare>) {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+
+        (ThresholdAccount::from_private_key(public_keys, &private_key).unwrap(), shares)
+    }
+
+    // Build the package of a call with a fresh nonce for each signer
+    fn package(
+        function_name: &str,
+        inputs: &[String],
+        input_types: &[&str],
+        signers: &[&SignerShare],
+        rng: &mut TestRng,
+    ) -> (RequestSigningPackage, Vec<SigningNonce>) {
+        let mut nonces = Vec::with_capacity(signers.len());
+        let mut commitments = Vec::with_capacity(signers.len());
+        for share in signers {
+            let (signing_nonces, signing_commitments) = preprocess(1, share.participant_index, rng);
+            nonces.push(signing_nonces[0]);
+            commitments.push(signing_commitments[0]);
+        }
+
+        let package = RequestSigningPackage::new(
+            ProgramID::from_str("credits.aleo").unwrap(),
+            FunctionName::from_str(function_name).unwrap(),
+            inputs.iter().map(|input| Value::from_str(input).unwrap()).collect(),
+            input_types.iter().map(|input_type| ValueType::from_str(input_type).unwrap()).collect(),
+            commitments,
+        )
+        .unwrap();
+
+        (package, nonces)
+    }
+
+    // Run the nonce share and signing rounds and aggregate the request
+    fn sign(
+        package: &mut RequestSigningPackage,
+        account: &ThresholdAccount,
+        signers: &[&SignerShare],
+        nonces: &[SigningNonce],
+        rng: &mut TestRng,
+    ) -> Result<Request<TestnetV0>> {
+        for (share, nonce) in signers.iter().zip(nonces) {
+            let nonce_shares = package.nonce_shares_for(account, share, nonce, rng)?;
+            package.add_nonce_shares(nonce_shares)?;
+        }
+
+        let partial_signatures = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonce)| package.partial_sign(account, share, nonce))
+            .collect::<Result<Vec<_>>>()?;
+
+        package.aggregate(account, &partial_signatures)
+    }
+
+    #[test]
+    fn test_public_request_verifies() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+        let signers = [&shares[0], &shares[2]];
+
+        let inputs = [RECIPIENT.to_string(), "10u64".to_string()];
+        let (mut package, nonces) = package("transfer_public", &inputs, &["address.public", "u64.public"], &signers, rng);
+        let request = sign(&mut package, &account, &signers, &nonces, rng).unwrap();
+
+        assert_eq!(*request.signer(), account.address().unwrap());
+        assert!(request.verify(&package.input_types));
+    }
+
+    #[test]
+    fn test_record_request_verifies() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+        let signers = [&shares[1], &shares[2]];
+
+        let record = format!("{{ owner: {}.private, microcredits: 100u64.private, _nonce: 0group.public }}", account.address().unwrap());
+        let inputs = [record, RECIPIENT.to_string(), "10u64".to_string()];
+        let input_types = ["credits.record", "address.private", "u64.private"];
+        let (mut package, nonces) = package("transfer_private", &inputs, &input_types, &signers, rng);
+        let request = sign(&mut package, &account, &signers, &nonces, rng).unwrap();
+
+        assert!(request.verify(&package.input_types));
+    }
+
+    #[test]
+    fn test_request_without_nonce_shares_is_refused() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+
+        let inputs = [RECIPIENT.to_string(), "10u64".to_string()];
+        let (package, nonces) = package("transfer_public", &inputs, &["address.public", "u64.public"], &[&shares[0], &shares[1]], rng);
+
+        assert!(package.partial_sign(&account, &shares[0], &nonces[0]).is_err());
+    }
+
+    #[test]
+    fn test_forged_nonce_shares_are_refused() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+        let signers = [&shares[0], &shares[1]];
+
+        let inputs = [RECIPIENT.to_string(), "10u64".to_string()];
+        let (mut package, nonces) = package("transfer_public", &inputs, &["address.public", "u64.public"], &signers, rng);
+
+        // Signer 2 sends nonce shares computed with another nonce than the one it committed to
+        let forged_nonce = SigningNonce::new(rng);
+        package.add_nonce_shares(package.nonce_shares_for(&account, &shares[0], &nonces[0], rng).unwrap()).unwrap();
+        package.add_nonce_shares(package.nonce_shares_for(&account, &shares[1], &forged_nonce, rng).unwrap()).unwrap();
+
+        assert!(package.partial_sign(&account, &shares[0], &nonces[0]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_partial_signature_is_identified() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+        let signers = [&shares[0], &shares[1]];
+
+        let inputs = [RECIPIENT.to_string(), "10u64".to_string()];
+        let (mut package, nonces) = package("transfer_public", &inputs, &["address.public", "u64.public"], &signers, rng);
+        for (share, nonce) in signers.iter().zip(&nonces) {
+            package.add_nonce_shares(package.nonce_shares_for(&account, share, nonce, rng).unwrap()).unwrap();
+        }
+
+        // Signer 2 sends a partial signature made with another nonce
+        let partial_signatures = vec![
+            package.partial_sign(&account, &shares[0], &nonces[0]).unwrap(),
+            package.partial_sign(&account, &shares[1], &SigningNonce::new(rng)).unwrap(),
+        ];
+
+        let error = package.aggregate(&account, &partial_signatures).unwrap_err();
+        assert!(error.to_string().contains(&format!("participant {}", shares[1].participant_index)));
+    }
+
+    #[test]
+    fn test_request_message_is_refused() {
+        let rng = &mut TestRng::default();
+        let (account, shares) = threshold_account(rng);
+        let signers = [&shares[0], &shares[1]];
+
+        let inputs = [RECIPIENT.to_string(), "10u64".to_string()];
+        let (mut package, nonces) = package("transfer_public", &inputs, &["address.public", "u64.public"], &signers, rng);
+        let request = sign(&mut package, &account, &signers, &nonces, rng).unwrap();
+
+        // A fields message holding the request's signed message [tvk, tcm, function id, input ids]
+        let mut fields = vec![*request.tvk(), *request.tcm(), package.function_id().unwrap()];
+        for input_id in request.input_ids() {
+            match input_id {
+                InputID::Public(hash) => fields.push(*hash),
+                input_id => panic!("Expected a public input id, found {input_id:?}"),
+            }
+        }
+        assert!(is_request_message(&fields).unwrap());
+
+        let (message_nonces, message_commitments) = preprocess(1, shares[0].participant_index, rng);
+        let message_package = SigningPackage::new(message_commitments, SigningMessage::from_fields(fields.clone()));
+
+        // The default policy refuses fields messages
+        let summary = SigningSummary::Message(MessageSummary { kind: MessageKind::Fields, message: SigningMessage::from_fields(fields) });
+        assert!(!SigningPolicy::default().violations(&summary, 0).is_empty());
+        // A policy allowing fields still never sees a summary of the request's message
+        assert!(message_package.summary().is_err());
+        assert!(message_package.partial_sign(&shares[0], &message_nonces[0], account.pr_sig).is_err());
+    }
+}
@@ -0,0 +1,621 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::{Identifier as FunctionName, ProgramID, Value, ValueType};
+use snarkvm_console_types_scalar::{bail, ensure, Result};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::*,
+    audit::*,
+    envelope::*,
+    frost::*,
+    generate_message::SigningMessage,
+    identifier::Identifier,
+    keys::*,
+    policy::*,
+    preprocess::*,
+    request::RequestSigningPackage,
+    spending::SpendingLedger,
+    utils::write_private,
+};
+
+/// The state of a participant in a signing session
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum SignerState {
+    // The nonce is generated and its commitment can be announced
+    Committed { nonce: SigningNonce, commitment: SigningCommitment },
+    // The nonce has been taken out of the session to sign, it can never be used again
+    Signing { commitment: SigningCommitment },
+    // The partial signature is ready to be sent
+    Signed { partial_signature: PartialThresholdSignature },
+    // The session ended without a signature
+    Aborted { reason: String },
+}
+
+/// A participant's side of one signing session
+///
+/// preprocess -> announce commitment -> (nonce shares, for requests) -> sign
+///
+/// Every transition is written to the session file before it takes effect, so a signer resuming
+/// after a crash finds the nonce either unused or gone: a nonce is erased from the file before
+/// the partial signature is computed, and a session found in `Signing` is aborted.
+/// The session file holds the secret nonce until then and is readable by its owner only.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignerSession {
+    pub session_id: String,
+    pub participant_index: Identifier,
+    // The unix timestamp the session was started at
+    pub created_at: u64,
+    // The session is aborted once this many seconds have passed
+    pub timeout_seconds: u64,
+    pub state: SignerState,
+    // The sequence number of the next envelope sent
+    pub next_sequence: u64,
+    // The sequence number of the last envelope received from the coordinator
+    pub last_received: Option<u64>,
+    // The last envelope sent to the coordinator, kept to be sent again as is
+    #[serde(default)]
+    pub last_sent: Option<Envelope>,
+    // The file the session is kept in
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl SignerSession {
+    // Start a session with a fresh nonce, saved to the session file
+    pub fn create<P: AsRef<Path>, R: Rng>(
+        path: P,
+        session_id: &str,
+        participant_index: Identifier,
+        timeout_seconds: u64,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::from_nonce(path, session_id, participant_index, SigningNonce::new(rng), timeout_seconds)
+    }
+
+    // Start a session with a preprocessed nonce, which the caller must not keep
+    pub fn from_nonce<P: AsRef<Path>>(
+        path: P,
+        session_id: &str,
+        participant_index: Identifier,
+        nonce: SigningNonce,
+        timeout_seconds: u64,
+    ) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        ensure!(!path.exists(), "Session file {} already exists", path.display());
+
+        let session = Self {
+            session_id: session_id.to_string(),
+            participant_index,
+            created_at: current_timestamp()?,
+            timeout_seconds,
+            state: SignerState::Committed { nonce, commitment: SigningCommitment::from(participant_index, &nonce) },
+            next_sequence: 0,
+            last_received: None,
+            last_sent: None,
+            path,
+        };
+        session.save()?;
+
+        Ok(session)
+    }
+
+    /// Resume a session from its file
+    ///
+    /// A session interrupted while signing is aborted, as its nonce is already gone.
+    pub fn resume<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut session: Self = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        session.path = path.as_ref().to_path_buf();
+
+        if let SignerState::Signing { .. } = session.state {
+            session.abort("The signer stopped while signing")?;
+        }
+
+        Ok(session)
+    }
+
+    // Write the session to its file and sync it, so an erased nonce is gone even after a power loss
+    fn save(&self) -> Result<()> {
+        write_private(&self.path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    // Move to the state and save the session
+    fn transition(&mut self, state: SignerState) -> Result<()> {
+        self.state = state;
+        self.save()
+    }
+
+    // Abort the session, erasing any unused nonce
+    pub fn abort(&mut self, reason: &str) -> Result<()> {
+        self.transition(SignerState::Aborted { reason: reason.to_string() })
+    }
+
+    // Abort the session if it has timed out
+    fn check_timeout(&mut self) -> Result<()> {
+        if current_timestamp()? > self.created_at.saturating_add(self.timeout_seconds) {
+            if !matches!(self.state, SignerState::Aborted { .. }) {
+                self.abort("The session timed out")?;
+            }
+            bail!("Session '{}' timed out", self.session_id);
+        }
+
+        Ok(())
+    }
+
+    // Wrap a payload for the coordinator with the next sequence number
+    fn envelope(&mut self, payload: Payload) -> Result<Envelope> {
+        let envelope = Envelope::new(&self.session_id, Party::Participant(self.participant_index), Party::Coordinator, self.next_sequence, payload)?;
+        self.next_sequence += 1;
+        self.last_sent = Some(envelope.clone());
+        self.save()?;
+
+        Ok(envelope)
+    }
+
+    // Announce the session's signing commitment to the coordinator
+    pub fn announce(&mut self) -> Result<Envelope> {
+        self.check_timeout()?;
+        match &self.state {
+            SignerState::Committed { commitment, .. } => {
+                let commitment = *commitment;
+                self.envelope(Payload::CommitmentAnnouncement(vec![commitment]))
+            }
+            state => bail!("Cannot announce a commitment in state {}", state.name()),
+        }
+    }
+
+    // Accept an envelope from the coordinator, returning the signing request it carries if any
+    pub fn receive(&mut self, envelope: &Envelope) -> Result<Option<SigningRequest>> {
+        self.check_timeout()?;
+        ensure!(envelope.sender == Party::Coordinator, "Signers only accept messages from the coordinator, found {}", envelope.sender);
+        envelope.check_order(&self.session_id, &[Party::Participant(self.participant_index), Party::Broadcast], self.last_received)?;
+        self.last_received = Some(envelope.sequence);
+        self.save()?;
+
+        match &envelope.payload {
+            Payload::SigningRequest(request) => Ok(Some((**request).clone())),
+            Payload::AggregationResult(result) => match &**result {
+                AggregationResult::Failure(reason) => {
+                    self.abort(reason)?;
+                    bail!("The coordinator aborted session '{}': {reason}", self.session_id)
+                }
+                _ => Ok(None),
+            },
+            payload => bail!("Signers do not accept a {}", payload.name()),
+        }
+    }
+
+    // Generate the nonce shares of a request, keeping the nonce
+    pub fn nonce_shares<R: Rng>(
+        &mut self,
+        package: &RequestSigningPackage,
+        account: &ThresholdAccount,
+        participant_signing_share: &SignerShare,
+        rng: &mut R,
+    ) -> Result<Envelope> {
+        self.check_timeout()?;
+        let nonce = match &self.state {
+            SignerState::Committed { nonce, commitment } => {
+                ensure!(package.signing_commitments.contains(commitment), "The request does not use this signer's commitment");
+                *nonce
+            }
+            state => bail!("Cannot generate nonce shares in state {}", state.name()),
+        };
+
+        let nonce_shares = package.nonce_shares_for(account, participant_signing_share, &nonce, rng)?;
+        self.envelope(Payload::NonceShares(Box::new(nonce_shares)))
+    }
+
+    /// Sign the coordinator's request once, if the signer's policy allows it
+    ///
+    /// The nonce is erased from the session file before the partial signature is computed.
+    /// A request that does not use this session's commitment is refused.
+    pub fn sign(
+        &mut self,
+        request: &SigningRequest,
+        account: &ThresholdAccount,
+        participant_signing_share: &SignerShare,
+        policy: &SigningPolicy,
+        ledger: &mut SpendingLedger,
+    ) -> Result<Envelope> {
+        self.check_timeout()?;
+        ensure!(participant_signing_share.participant_index == self.participant_index, "The key share does not belong to this session's participant");
+
+        let (nonce, commitment) = match &self.state {
+            SignerState::Committed { nonce, commitment } => (*nonce, *commitment),
+            state => bail!("Cannot sign in state {}", state.name()),
+        };
+        ensure!(request.signing_commitments().contains(&commitment), "The signing request does not use this signer's commitment");
+
+        // Erase the nonce before using it
+        self.transition(SignerState::Signing { commitment })?;
+
+        let partial_signature = match request {
+            SigningRequest::Message(package) => policy.partial_sign(package, ledger, participant_signing_share, &nonce, account.pr_sig),
+            SigningRequest::Request(package) => policy.partial_sign_request(package, ledger, account, participant_signing_share, &nonce),
+        };
+        let partial_signature = match partial_signature {
+            Ok(partial_signature) => partial_signature,
+            Err(error) => {
+                self.abort(&error.to_string())?;
+                return Err(error);
+            }
+        };
+
+        self.transition(SignerState::Signed { partial_signature })?;
+        self.envelope(Payload::PartialSignature(partial_signature))
+    }
+
+    /// Send the last envelope again, for a signer that cannot tell whether the coordinator received it
+    ///
+    /// The envelope is kept in the session file and resent as is, with its sequence number, so
+    /// the coordinator accepts it once and ignores the copies. A partial signature is resent
+    /// without its nonce, which is already gone.
+    pub fn resend(&self) -> Result<Envelope> {
+        match (&self.state, &self.last_sent) {
+            (SignerState::Committed { .. } | SignerState::Signed { .. }, Some(envelope)) => Ok(envelope.clone()),
+            (SignerState::Committed { .. } | SignerState::Signed { .. }, None) => bail!("Session '{}' has sent nothing yet", self.session_id),
+            (state, _) => bail!("Cannot resend an envelope in state {}", state.name()),
+        }
+    }
+
+    /// The audit event of a step of this session, for the signer's audit log
+    ///
+    /// The event covers the request's package if one is given, or the session's own commitment.
+    pub fn audit_event(&self, kind: AuditEventKind, request: Option<&SigningRequest>, outcome: AuditOutcome) -> Result<AuditEvent> {
+        let commitments = match (request, &self.state) {
+            (Some(request), _) => request.signing_commitments().clone(),
+            (None, SignerState::Committed { commitment, .. } | SignerState::Signing { commitment }) => vec![*commitment],
+            (None, _) => Vec::new(),
+        };
+
+        Ok(AuditEvent {
+            kind,
+            session_id: self.session_id.clone(),
+            message_hash: request.map(|request| request.message_hash()).transpose()?,
+            participants: vec![self.participant_index],
+            commitments,
+            outcome,
+        })
+    }
+}
+
+impl SignerState {
+    // The name of the state, for errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Committed { .. } => "committed",
+            Self::Signing { .. } => "signing",
+            Self::Signed { .. } => "signed",
+            Self::Aborted { .. } => "aborted",
+        }
+    }
+}
+
+/// The state of the coordinator of a signing session
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum CoordinatorState {
+    // Waiting for every signer's commitment
+    CollectingCommitments,
+    // Waiting for every signer's nonce shares for the request
+    CollectingNonceShares { package: RequestSigningPackage },
+    // Waiting for every signer's partial signature
+    CollectingSignatures { request: SigningRequest },
+    // The signature is aggregated
+    Complete { result: AggregationResult },
+    // The session ended without a signature
+    Aborted { reason: String },
+}
+
+/// The coordinator's side of one signing session
+///
+/// collect commitments -> send the signing request -> (collect nonce shares, resend the request)
+/// -> collect partial signatures -> aggregate
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoordinatorSession {
+    pub session_id: String,
+    // The participants chosen to sign
+    pub signers: Vec<Identifier>,
+    // The unix timestamp the session was started at
+    pub created_at: u64,
+    // The session is aborted once this many seconds have passed
+    pub timeout_seconds: u64,
+    pub state: CoordinatorState,
+    // Each signer's commitment
+    pub commitments: HashMap<Identifier, SigningCommitment>,
+    // Each signer's partial signature
+    pub partial_signatures: HashMap<Identifier, PartialThresholdSignature>,
+    // The signers whose nonce shares were received
+    pub nonce_senders: Vec<Identifier>,
+    // The sequence number of the next envelope sent
+    pub next_sequence: u64,
+    // The sequence number of the last envelope received from each signer
+    pub last_received: HashMap<Identifier, u64>,
+    // The last envelope received from each signer, a copy of which is ignored
+    #[serde(default)]
+    pub last_envelopes: HashMap<Identifier, Envelope>,
+}
+
+impl CoordinatorSession {
+    pub fn new(session_id: &str, signers: Vec<Identifier>, timeout_seconds: u64) -> Result<Self> {
+        ensure!(!signers.is_empty(), "A signing session needs signers");
+        let mut unique_signers = signers.clone();
+        unique_signers.sort();
+        unique_signers.dedup();
+        ensure!(unique_signers.len() == signers.len(), "Duplicate signers in session '{session_id}'");
+
+        Ok(Self {
+            session_id: session_id.to_string(),
+            signers: unique_signers,
+            created_at: current_timestamp()?,
+            timeout_seconds,
+            state: CoordinatorState::CollectingCommitments,
+            commitments: HashMap::new(),
+            partial_signatures: HashMap::new(),
+            nonce_senders: Vec::new(),
+            next_sequence: 0,
+            last_received: HashMap::new(),
+            last_envelopes: HashMap::new(),
+        })
+    }
+
+    // Read a session from a JSON file on disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    // Write the session to disk as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // Abort the session, telling the signers why
+    pub fn abort(&mut self, reason: &str) -> Result<Envelope> {
+        self.state = CoordinatorState::Aborted { reason: reason.to_string() };
+        self.broadcast(Payload::AggregationResult(Box::new(AggregationResult::Failure(reason.to_string()))))
+    }
+
+    // Refuse to advance the session once it has timed out, see `expire`
+    fn check_timeout(&self) -> Result<()> {
+        if current_timestamp()? > self.created_at.saturating_add(self.timeout_seconds) {
+            bail!("Session '{}' timed out", self.session_id);
+        }
+
+        Ok(())
+    }
+
+    /// Abort the session if it has timed out, returning the abort to send to the signers
+    ///
+    /// The abort is the only envelope a timed out session produces, so its sequence number is
+    /// only taken when the caller is there to send it.
+    pub fn expire(&mut self) -> Result<Option<Envelope>> {
+        let timed_out = current_timestamp()? > self.created_at.saturating_add(self.timeout_seconds);
+        match timed_out && !matches!(self.state, CoordinatorState::Aborted { .. } | CoordinatorState::Complete { .. }) {
+            true => Ok(Some(self.abort("The session timed out")?)),
+            false => Ok(None),
+        }
+    }
+
+    // Wrap a payload for every signer with the next sequence number
+    fn broadcast(&mut self, payload: Payload) -> Result<Envelope> {
+        let envelope = Envelope::new(&self.session_id, Party::Coordinator, Party::Broadcast, self.next_sequence, payload)?;
+        self.next_sequence += 1;
+
+        Ok(envelope)
+    }
+
+    // The signers' commitments, sorted by identifier so every package lists them the same way
+    fn signing_commitments(&self) -> Vec<SigningCommitment> {
+        self.signers.iter().filter_map(|signer| self.commitments.get(signer).copied()).collect()
+    }
+
+    /// Accept an envelope from a signer
+    ///
+    /// Messages out of order, from parties outside the session or not expected in the current
+    /// state are rejected without changing the session.
+    pub fn receive(&mut self, envelope: &Envelope) -> Result<()> {
+        self.check_timeout()?;
+        let sender = match envelope.sender {
+            Party::Participant(sender) if self.signers.contains(&sender) => sender,
+            sender => bail!("{sender} is not a signer of session '{}'", self.session_id),
+        };
+        // A signer that could not tell whether its last envelope arrived sends it again as is
+        if self.last_envelopes.get(&sender) == Some(envelope) {
+            return Ok(());
+        }
+        envelope.check_order(&self.session_id, &[Party::Coordinator], self.last_received.get(&sender).copied())?;
+
+        match (&mut self.state, &envelope.payload) {
+            (CoordinatorState::CollectingCommitments, Payload::CommitmentAnnouncement(commitments)) => {
+                ensure!(commitments.len() == 1, "Expected one commitment from participant {sender}, found {}", commitments.len());
+                ensure!(!self.commitments.contains_key(&sender), "Participant {sender} already announced its commitment");
+                self.commitments.insert(sender, commitments[0]);
+            }
+            (CoordinatorState::CollectingNonceShares { package }, Payload::NonceShares(nonce_shares)) => {
+                ensure!(!self.nonce_senders.contains(&sender), "Participant {sender} already sent its nonce shares");
+                package.add_nonce_shares((**nonce_shares).clone())?;
+                self.nonce_senders.push(sender);
+            }
+            (CoordinatorState::CollectingSignatures { .. }, Payload::PartialSignature(partial_signature)) => {
+                // A signer resuming after a crash sends its partial signature again
+                match self.partial_signatures.get(&sender) {
+                    Some(existing) => ensure!(existing == partial_signature, "Participant {sender} sent two different partial signatures"),
+                    None => {
+                        self.partial_signatures.insert(sender, *partial_signature);
+                    }
+                }
+            }
+            (state, payload) => bail!("Cannot accept a {} in state {}", payload.name(), state.name()),
+        }
+
+        self.last_received.insert(sender, envelope.sequence);
+        self.last_envelopes.insert(sender, envelope.clone());
+        Ok(())
+    }
+
+    // Check that every signer has announced its commitment
+    fn ensure_committed(&self) -> Result<()> {
+        ensure!(
+            matches!(self.state, CoordinatorState::CollectingCommitments),
+            "Cannot send a signing request in state {}", self.state.name()
+        );
+        ensure!(
+            self.commitments.len() == self.signers.len(),
+            "Waiting for {} of {} commitments", self.signers.len() - self.commitments.len(), self.signers.len()
+        );
+
+        Ok(())
+    }
+
+    // Ask the signers to sign a message once every commitment is in
+    pub fn request_message(&mut self, message: SigningMessage) -> Result<Envelope> {
+        self.check_timeout()?;
+        self.ensure_committed()?;
+
+        let request = SigningRequest::Message(SigningPackage::new(self.signing_commitments(), message));
+        self.state = CoordinatorState::CollectingSignatures { request: request.clone() };
+        self.broadcast(Payload::SigningRequest(Box::new(request)))
+    }
+
+    /// Ask the signers to sign a function call once every commitment is in
+    ///
+    /// The signers' nonce shares are collected first; the request is sent again with the shares
+    /// once all of them are in, see `request_with_nonce_shares`.
+    pub fn request_call(
+        &mut self,
+        program_id: ProgramID<TestnetV0>,
+        function_name: FunctionName<TestnetV0>,
+        inputs: Vec<Value<TestnetV0>>,
+        input_types: Vec<ValueType<TestnetV0>>,
+    ) -> Result<Envelope> {
+        self.check_timeout()?;
+        self.ensure_committed()?;
+
+        let package = RequestSigningPackage::new(program_id, function_name, inputs, input_types, self.signing_commitments())?;
+        self.state = CoordinatorState::CollectingNonceShares { package: package.clone() };
+        self.broadcast(Payload::SigningRequest(Box::new(SigningRequest::Request(package))))
+    }
+
+    // Send the request again with every signer's nonce shares
+    pub fn request_with_nonce_shares(&mut self) -> Result<Envelope> {
+        self.check_timeout()?;
+        let package = match &self.state {
+            CoordinatorState::CollectingNonceShares { package } => package.clone(),
+            state => bail!("Cannot send nonce shares in state {}", state.name()),
+        };
+        ensure!(
+            self.nonce_senders.len() == self.signers.len(),
+            "Waiting for {} of {} nonce shares", self.signers.len() - self.nonce_senders.len(), self.signers.len()
+        );
+
+        let request = SigningRequest::Request(package);
+        self.state = CoordinatorState::CollectingSignatures { request: request.clone() };
+        self.broadcast(Payload::SigningRequest(Box::new(request)))
+    }
+
+    /// The audit event of a step of this session, for the coordinator's audit log
+    ///
+    /// The message hash is taken from the package the session is collecting for, so events of
+    /// the aggregation are built before the session completes.
+    pub fn audit_event(&self, kind: AuditEventKind, outcome: AuditOutcome) -> Result<AuditEvent> {
+        let message_hash = match &self.state {
+            CoordinatorState::CollectingNonceShares { package } => Some(message_hash(&package.binding_message()?)?),
+            CoordinatorState::CollectingSignatures { request } => Some(request.message_hash()?),
+            _ => None,
+        };
+
+        Ok(AuditEvent {
+            kind,
+            session_id: self.session_id.clone(),
+            message_hash,
+            participants: self.signers.clone(),
+            commitments: self.signing_commitments(),
+            outcome,
+        })
+    }
+
+    /// Aggregate the partial signatures once all of them are in
+    ///
+    /// A signature that fails to verify aborts the session.
+    pub fn aggregate(&mut self, account: &ThresholdAccount) -> Result<Envelope> {
+        self.check_timeout()?;
+        let request = match &self.state {
+            CoordinatorState::CollectingSignatures { request } => request.clone(),
+            state => bail!("Cannot aggregate in state {}", state.name()),
+        };
+        ensure!(
+            self.partial_signatures.len() == self.signers.len(),
+            "Waiting for {} of {} partial signatures", self.signers.len() - self.partial_signatures.len(), self.signers.len()
+        );
+
+        let partial_signatures: Vec<PartialThresholdSignature> =
+            self.signers.iter().filter_map(|signer| self.partial_signatures.get(signer).copied()).collect();
+        let result = match &request {
+            SigningRequest::Message(package) => package.aggregate(account, &partial_signatures).map(AggregationResult::Signature),
+            SigningRequest::Request(package) => {
+                package.aggregate(account, &partial_signatures).map(|request| AggregationResult::Request(Box::new(request)))
+            }
+        };
+
+        match result {
+            Ok(result) => {
+                self.state = CoordinatorState::Complete { result: result.clone() };
+                self.broadcast(Payload::AggregationResult(Box::new(result)))
+            }
+            Err(error) => self.abort(&error.to_string()),
+        }
+    }
+}
+
+impl CoordinatorState {
+    // The name of the state, for errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CollectingCommitments => "collecting commitments",
+            Self::CollectingNonceShares { .. } => "collecting nonce shares",
+            Self::CollectingSignatures { .. } => "collecting signatures",
+            Self::Complete { .. } => "complete",
+            Self::Aborted { .. } => "aborted",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_types_scalar::TestRng;
+
+    #[test]
+    fn test_resent_announcement_is_accepted_once() {
+        let rng = &mut TestRng::default();
+        let participant_index = Identifier::from_u64(1).unwrap();
+        let path = std::env::temp_dir().join(format!("aleo-frost-session-{}.json", rng.gen::<u64>()));
+        let mut signer = SignerSession::create(&path, "session-1", participant_index, 600, rng).unwrap();
+        let mut coordinator = CoordinatorSession::new("session-1", vec![participant_index], 600).unwrap();
+
+        // The announcement is resent after a failed POST and again after a restart
+        let announcement = signer.announce().unwrap();
+        assert_eq!(signer.resend().unwrap(), announcement);
+        coordinator.receive(&announcement).unwrap();
+        let resumed = SignerSession::resume(&path).unwrap();
+        coordinator.receive(&resumed.resend().unwrap()).unwrap();
+        assert_eq!(coordinator.commitments.len(), 1);
+
+        // A second announcement with a new sequence number is still refused
+        assert!(coordinator.receive(&signer.announce().unwrap()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
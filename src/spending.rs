@@ -0,0 +1,252 @@
+use std::path::{Path, PathBuf};
+
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_program::{Identifier as FunctionName, Literal, ProgramID, Value};
+use snarkvm_console_types_scalar::Result;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{policy::collect_literals, summary::*, utils::write_private};
+
+/// How long approvals are kept in the ledger, in seconds, unless a limit has a longer window
+///
+/// A fixed period keeps the history when limits are removed or their windows are widened later.
+const RETENTION_SECONDS: u64 = 90 * 86_400;
+
+/// A cap on the amount a signer approves for a program within a rolling window
+///
+/// The limit applies to every function of the program if no function is given.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpendingLimit {
+    pub program_id: ProgramID<TestnetV0>,
+    #[serde(default)]
+    pub function_name: Option<FunctionName<TestnetV0>>,
+    // The length of the rolling window in seconds, e.g. 86400 for 24h
+    pub window_seconds: u64,
+    // The most that may be approved within any window, e.g. 10000000000 microcredits for 10,000 credits
+    pub max_amount: u128,
+}
+
+impl SpendingLimit {
+    fn applies_to(&self, program_id: &ProgramID<TestnetV0>, function_name: &FunctionName<TestnetV0>) -> bool {
+        self.program_id == *program_id && self.function_name.as_ref().map_or(true, |name| name == function_name)
+    }
+}
+
+/// An amount approved by the signer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingEntry {
+    // The unix timestamp the amount was approved at
+    pub timestamp: u64,
+    pub program_id: ProgramID<TestnetV0>,
+    pub function_name: FunctionName<TestnetV0>,
+    pub amount: u128,
+}
+
+/// A signer's persistent record of the amounts it approved
+///
+/// Every approval is written to disk before the signer produces its partial signature, so
+/// the limits hold across restarts. Approvals whose signing later fails still count.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLedger {
+    pub entries: Vec<SpendingEntry>,
+    // The file the ledger is kept in
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl SpendingLedger {
+    // Read the ledger from a JSON file on disk, starting an empty ledger if the file does not exist
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut ledger: Self = match path.exists() {
+            true => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+            false => Self::default(),
+        };
+        ledger.path = path;
+
+        Ok(ledger)
+    }
+
+    // Write the ledger to its file durably, replacing the previous file only once fully written
+    pub fn save(&self) -> Result<()> {
+        write_private(&self.path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    // The total approved for the limit in the window ending at the timestamp
+    pub fn spent(&self, limit: &SpendingLimit, timestamp: u64) -> u128 {
+        let since = timestamp.saturating_sub(limit.window_seconds);
+        self.entries
+            .iter()
+            .filter(|entry| entry.timestamp > since && limit.applies_to(&entry.program_id, &entry.function_name))
+            .map(|entry| entry.amount)
+            .fold(0u128, |total, amount| total.saturating_add(amount))
+    }
+
+    /// Collect the limits the summary would exceed if approved at the timestamp
+    pub fn violations(&self, limits: &[SpendingLimit], summary: &SigningSummary, timestamp: u64) -> Vec<String> {
+        let (program_id, function_name, amount) = match spending_of(summary) {
+            Some(spending) => spending,
+            None => return Vec::new(),
+        };
+
+        limits
+            .iter()
+            .filter(|limit| limit.applies_to(&program_id, &function_name))
+            .filter_map(|limit| {
+                let spent = self.spent(limit, timestamp);
+                match spent.saturating_add(amount) > limit.max_amount {
+                    true => Some(format!(
+                        "{program_id}/{function_name} would bring the total approved for {} to {}, above {} per {}s",
+                        limit.function_name.map_or_else(|| program_id.to_string(), |name| format!("{program_id}/{name}")),
+                        spent.saturating_add(amount),
+                        limit.max_amount,
+                        limit.window_seconds,
+                    )),
+                    false => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Record an approved summary and persist the ledger
+    ///
+    /// Entries older than the retention period and the longest window are dropped, they no longer
+    /// count towards any limit.
+    pub fn record(&mut self, limits: &[SpendingLimit], summary: &SigningSummary, timestamp: u64) -> Result<()> {
+        let (program_id, function_name, amount) = match spending_of(summary) {
+            Some(spending) => spending,
+            None => return Ok(()),
+        };
+
+        let retention = limits.iter().map(|limit| limit.window_seconds).fold(RETENTION_SECONDS, u64::max);
+        self.entries.retain(|entry| entry.timestamp > timestamp.saturating_sub(retention));
+        self.entries.push(SpendingEntry { timestamp, program_id, function_name, amount });
+
+        self.save()
+    }
+}
+
+/// The amount a summary spends, keyed by program and function
+///
+/// This is the transfer or fee amount for credits.aleo and the sum of the u64 and u128
+/// literals in the inputs of any other function, including those inside structs and arrays,
+/// the same literals the policy's amount limits see. Messages and deployments spend nothing.
+pub fn spending_of(summary: &SigningSummary) -> Option<(ProgramID<TestnetV0>, FunctionName<TestnetV0>, u128)> {
+    let call = match summary {
+        SigningSummary::Call(call) => call,
+        SigningSummary::Message(..) | SigningSummary::Deployment(..) => return None,
+    };
+
+    let amount = match &call.transfer {
+        Some(transfer) => transfer.amount as u128,
+        None => {
+            let mut literals = Vec::new();
+            for (_, input) in &call.inputs {
+                if let Value::Plaintext(plaintext) = input {
+                    collect_literals(plaintext, &mut literals);
+                }
+            }
+            literals
+                .iter()
+                .map(|literal| match literal {
+                    Literal::U64(amount) => **amount as u128,
+                    Literal::U128(amount) => **amount,
+                    _ => 0,
+                })
+                .fold(0u128, |total, amount| total.saturating_add(amount))
+        }
+    };
+
+    Some((call.program_id, call.function_name, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use snarkvm_console_program::ValueType;
+    use snarkvm_console_types_scalar::TestRng;
+    use std::str::FromStr;
+
+    use rand::Rng;
+
+    const DAY: u64 = 86_400;
+
+    // A call moving the amount inside a struct input
+    fn call(amount: u64) -> SigningSummary {
+        SigningSummary::Call(CallSummary {
+            program_id: ProgramID::from_str("token.aleo").unwrap(),
+            function_name: FunctionName::from_str("transfer").unwrap(),
+            inputs: vec![(
+                ValueType::from_str("transfer_args.public").unwrap(),
+                Value::from_str(&format!("{{ amounts: [{amount}u64, 0u64], memo: 1field }}")).unwrap(),
+            )],
+            transfer: None,
+        })
+    }
+
+    fn limits() -> Vec<SpendingLimit> {
+        vec![SpendingLimit {
+            program_id: ProgramID::from_str("token.aleo").unwrap(),
+            function_name: None,
+            window_seconds: DAY,
+            max_amount: 1_000,
+        }]
+    }
+
+    fn temp_path(rng: &mut TestRng) -> PathBuf {
+        std::env::temp_dir().join(format!("aleo-frost-spending-{}.json", rng.gen::<u64>()))
+    }
+
+    #[test]
+    fn test_nested_amounts_are_counted() {
+        let (_, _, amount) = spending_of(&call(600)).unwrap();
+        assert_eq!(amount, 600);
+    }
+
+    #[test]
+    fn test_spend_over_the_limit_is_refused() {
+        let rng = &mut TestRng::default();
+        let path = temp_path(rng);
+        let mut ledger = SpendingLedger::open(&path).unwrap();
+        let now = 100 * DAY;
+
+        assert!(ledger.violations(&limits(), &call(600), now).is_empty());
+        ledger.record(&limits(), &call(600), now).unwrap();
+        assert_eq!(ledger.violations(&limits(), &call(600), now + 1).len(), 1);
+        assert!(ledger.violations(&limits(), &call(400), now + 1).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_window_rolls_over() {
+        let rng = &mut TestRng::default();
+        let path = temp_path(rng);
+        let mut ledger = SpendingLedger::open(&path).unwrap();
+        let now = 100 * DAY;
+
+        ledger.record(&limits(), &call(600), now).unwrap();
+        assert_eq!(ledger.spent(&limits()[0], now + DAY - 1), 600);
+        // Once the window has passed, the approval no longer counts
+        assert_eq!(ledger.spent(&limits()[0], now + DAY), 0);
+        assert!(ledger.violations(&limits(), &call(600), now + DAY).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_ledger_survives_a_restart() {
+        let rng = &mut TestRng::default();
+        let path = temp_path(rng);
+        let now = 100 * DAY;
+
+        let mut ledger = SpendingLedger::open(&path).unwrap();
+        ledger.record(&limits(), &call(600), now).unwrap();
+
+        let reopened = SpendingLedger::open(&path).unwrap();
+        assert_eq!(reopened.entries, ledger.entries);
+        assert_eq!(reopened.violations(&limits(), &call(600), now + 1).len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+use snarkvm_console_account::Address;
+use snarkvm_console_network::{Network, TestnetV0};
+use snarkvm_console_program::{Identifier as FunctionName, Literal, Plaintext, PlaintextType, ProgramID, Value, ValueType};
+use snarkvm_console_types::Field;
+use snarkvm_console_types_scalar::{bail, ensure, Result, ToBits, ToBytes};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{deployment::DeploymentSigningPackage, frost::SigningPackage, generate_message::*, request::*};
+
+/// The number of microcredits in one credit
+const MICROCREDITS_PER_CREDIT: u64 = 1_000_000;
+
+/// A human-readable description of what a signer approves by signing a package
+///
+/// The summary is derived from the payload alone and displays deterministically, so every
+/// signer shown the same package sees the same text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigningSummary {
+    // A call of an Aleo function from the threshold account
+    Call(CallSummary),
+    // A message signed by the threshold account
+    Message(MessageSummary),
+    // A program deployed with the threshold account as its owner
+    Deployment(DeploymentSummary),
+}
+
+/// The program, function and inputs of a function call
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSummary {
+    // The program of the function call
+    pub program_id: ProgramID<TestnetV0>,
+    // The function called
+    pub function_name: FunctionName<TestnetV0>,
+    // The function inputs with their types
+    pub inputs: Vec<(ValueType<TestnetV0>, Value<TestnetV0>)>,
+    // The credits moved by the call, for credits.aleo transfers and fees
+    pub transfer: Option<TransferSummary>,
+}
+
+/// The credits moved by a credits.aleo transfer or fee
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferSummary {
+    // The recipient of the credits, fees have none
+    pub recipient: Option<Address<TestnetV0>>,
+    // The amount in microcredits
+    pub amount: u64,
+}
+
+/// A message as recovered from the fields signed by the group
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSummary {
+    // The kind of payload the message was built from
+    pub kind: MessageKind,
+    // The decoded message
+    pub message: SigningMessage,
+}
+
+/// The program and verifying keys of a deployment
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentSummary {
+    // The program deployed
+    pub program_id: ProgramID<TestnetV0>,
+    // The edition of the program
+    pub edition: u16,
+    // The deployment id signed by the program owner
+    pub deployment_id: Field<TestnetV0>,
+    // The BHP1024 hash of each function's verifying key, to compare against a local build
+    pub verifying_keys: Vec<(FunctionName<TestnetV0>, Field<TestnetV0>)>,
+}
+
+/// Derives the summary of a signing package
+///
+/// Signers refuse to produce a partial signature for a package without a summary.
+pub trait Summarize {
+    fn summary(&self) -> Result<SigningSummary>;
+}
+
+impl Summarize for SigningPackage {
+    /// The message is decoded back from the fields that are signed, so the summary shows
+    /// what the signature covers rather than what the coordinator claims it covers. Messages that
+    /// could be read as a request's signed message have no summary.
+    fn summary(&self) -> Result<SigningSummary> {
+        let kind = self.message.kind();
+        let fields = self.message.to_fields()?;
+        ensure!(!is_request_message(&fields)?, "The {kind:?} message has the form of a signed request");
+
+        let message = SigningMessage::decode(&fields, kind)?;
+        ensure!(message.to_fields()? == fields, "The {kind:?} message cannot be recovered from the signed fields");
+
+        Ok(SigningSummary::Message(MessageSummary { kind, message }))
+    }
+}
+
+impl Summarize for RequestSigningPackage {
+    fn summary(&self) -> Result<SigningSummary> {
+        ensure!(
+            self.inputs.len() == self.input_types.len(),
+            "Function '{}/{}' expects {} inputs, found {}", self.program_id, self.function_name, self.input_types.len(), self.inputs.len()
+        );
+        for (input, input_type) in self.inputs.iter().zip(&self.input_types) {
+            check_input_type(input, input_type)?;
+        }
+
+        let transfer = match self.program_id.to_string() == "credits.aleo" {
+            true => credits_transfer(&self.function_name.to_string(), &self.inputs)?,
+            false => None,
+        };
+
+        Ok(SigningSummary::Call(CallSummary {
+            program_id: self.program_id,
+            function_name: self.function_name,
+            inputs: self.input_types.iter().cloned().zip(self.inputs.iter().cloned()).collect(),
+            transfer,
+        }))
+    }
+}
+
+impl Summarize for DeploymentSigningPackage {
+    /// The deployment id is recomputed from the deployment, so the program shown is the one signed.
+    fn summary(&self) -> Result<SigningSummary> {
+        let verifying_keys = self
+            .deployment
+            .verifying_keys()
+            .iter()
+            .map(|(function_name, (verifying_key, _))| {
+                Ok((*function_name, TestnetV0::hash_bhp1024(&verifying_key.to_bytes_le()?.to_bits_le())?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SigningSummary::Deployment(DeploymentSummary {
+            program_id: *self.deployment.program_id(),
+            edition: self.deployment.edition(),
+            deployment_id: self.deployment_id()?,
+            verifying_keys,
+        }))
+    }
+}
+
+// Check that an input has the kind of value its type declares
+fn check_input_type(input: &Value<TestnetV0>, input_type: &ValueType<TestnetV0>) -> Result<()> {
+    match (input_type, input) {
+        (ValueType::Constant(plaintext_type) | ValueType::Public(plaintext_type) | ValueType::Private(plaintext_type), Value::Plaintext(plaintext)) => {
+            match (plaintext_type, plaintext) {
+                (PlaintextType::Literal(literal_type), Plaintext::Literal(literal, ..)) => {
+                    ensure!(literal.to_type() == *literal_type, "Expected a '{input_type}' input, found '{input}'")
+                }
+                (PlaintextType::Literal(..), _) => bail!("Expected a '{input_type}' input, found '{input}'"),
+                _ => {}
+            }
+        }
+        (ValueType::Record(..) | ValueType::ExternalRecord(..), Value::Record(..)) => {}
+        _ => bail!("Expected a '{input_type}' input, found '{input}'"),
+    }
+
+    Ok(())
+}
+
+// Derive the recipient and amount of the credits.aleo functions that move credits
+fn credits_transfer(function_name: &str, inputs: &[Value<TestnetV0>]) -> Result<Option<TransferSummary>> {
+    let transfer = match function_name {
+        // transfer_public(recipient, amount), transfer_public_as_signer(..), transfer_public_to_private(..)
+        "transfer_public" | "transfer_public_as_signer" | "transfer_public_to_private" => {
+            TransferSummary { recipient: Some(address_input(inputs, 0)?), amount: u64_input(inputs, 1)? }
+        }
+        // transfer_private(record, recipient, amount), transfer_private_to_public(..)
+        "transfer_private" | "transfer_private_to_public" => {
+            TransferSummary { recipient: Some(address_input(inputs, 1)?), amount: u64_input(inputs, 2)? }
+        }
+        // fee_public(base_fee, priority_fee, execution_id)
+        "fee_public" => TransferSummary { recipient: None, amount: fee_amount(u64_input(inputs, 0)?, u64_input(inputs, 1)?)? },
+        // fee_private(record, base_fee, priority_fee, execution_id)
+        "fee_private" => TransferSummary { recipient: None, amount: fee_amount(u64_input(inputs, 1)?, u64_input(inputs, 2)?)? },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(transfer))
+}
+
+fn fee_amount(base_fee: u64, priority_fee: u64) -> Result<u64> {
+    match base_fee.checked_add(priority_fee) {
+        Some(amount) => Ok(amount),
+        None => bail!("The fee overflows: {base_fee} + {priority_fee} microcredits"),
+    }
+}
+
+fn address_input(inputs: &[Value<TestnetV0>], index: usize) -> Result<Address<TestnetV0>> {
+    match inputs.get(index) {
+        Some(Value::Plaintext(Plaintext::Literal(Literal::Address(address), ..))) => Ok(*address),
+        _ => bail!("Expected an address for input {index}"),
+    }
+}
+
+fn u64_input(inputs: &[Value<TestnetV0>], index: usize) -> Result<u64> {
+    match inputs.get(index) {
+        Some(Value::Plaintext(Plaintext::Literal(Literal::U64(amount), ..))) => Ok(**amount),
+        _ => bail!("Expected a u64 amount for input {index}"),
+    }
+}
+
+// Format microcredits as credits, e.g. 1500000 as "1.5"
+fn format_credits(microcredits: u64) -> String {
+    let credits = microcredits / MICROCREDITS_PER_CREDIT;
+    let fraction = microcredits % MICROCREDITS_PER_CREDIT;
+
+    match fraction {
+        0 => format!("{credits}"),
+        _ => format!("{credits}.{}", format!("{fraction:06}").trim_end_matches('0')),
+    }
+}
+
+impl fmt::Display for SigningSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Call(call) => {
+                if let Some(transfer) = &call.transfer {
+                    match transfer.recipient {
+                        Some(recipient) => {
+                            writeln!(f, "{} {} credits to {recipient}", call.function_name, format_credits(transfer.amount))?
+                        }
+                        None => writeln!(f, "{} {} credits", call.function_name, format_credits(transfer.amount))?,
+                    }
+                }
+                write!(f, "call {}/{}", call.program_id, call.function_name)?;
+                for (index, (input_type, input)) in call.inputs.iter().enumerate() {
+                    write!(f, "\n  input {index} ({input_type}): {input}")?;
+                }
+                Ok(())
+            }
+            Self::Message(message) => write!(f, "sign {:?} message: {}", message.kind, message.message),
+            Self::Deployment(deployment) => {
+                write!(f, "deploy {} (edition {}) as program owner", deployment.program_id, deployment.edition)?;
+                write!(f, "\n  deployment id: {}", deployment.deployment_id)?;
+                for (function_name, checksum) in &deployment.verifying_keys {
+                    write!(f, "\n  verifying key {function_name}: {checksum}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,21 +1,47 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
 
-use rand::Error;
 use snarkvm_console_network::{Network, TestnetV0};
-use snarkvm_console_types::{Group, Scalar, U64};
-use snarkvm_console_types_scalar::{FromField, ToField, One, Zero, Inverse, Field, anyhow};
+use snarkvm_console_types::{Group, Scalar};
+use snarkvm_console_types_scalar::{ToField, One, Zero, Inverse, Field, Result, anyhow};
 
-use crate::preprocess::SigningCommitment;
+use crate::{hierarchy::AccessStructure, identifier::Identifier, preprocess::SigningCommitment};
 
-// Calculate the Lagrange coefficient for a given participant index.
+/// Calculate the Lagrange coefficient for a given participant identifier.
+///
+/// With an access structure, the participant is a member of a hierarchical (nested threshold)
+/// structure and the coefficient is the product of the Lagrange coefficients along its path in the
+/// tree, each computed over the satisfied children of the node, so Sum coeff_j * s_j = secret.
+/// A participant in a subtree that is not satisfied by the signers has a coefficient of zero.
 pub fn calculate_lagrange_coefficients(
-    participant_index: u64,
-    all_participant_indices: &[u64],
-) -> Result<Scalar<TestnetV0>, Error> {
+    participant_index: Identifier,
+    all_participant_indices: &[Identifier],
+    access_structure: Option<&AccessStructure>,
+) -> Result<Scalar<TestnetV0>> {
+    let mut seen = HashSet::with_capacity(all_participant_indices.len());
+    if !all_participant_indices.iter().all(|index| seen.insert(*index)) {
+        return Err(anyhow!("There was a duplicate index"));
+    }
+
+    if let Some(access_structure) = access_structure {
+        let coefficients = match access_structure.lagrange_coefficients(all_participant_indices)? {
+            Some(coefficients) => coefficients,
+            None => return Err(anyhow!("The signing participants do not satisfy the access structure")),
+        };
+
+        return Ok(coefficients.get(&participant_index).copied().unwrap_or_else(Scalar::<TestnetV0>::zero));
+    }
+
     let mut numerator = Scalar::<TestnetV0>::one();
     let mut denominator = Scalar::<TestnetV0>::one();
 
-    let participant_index_scalar = Scalar::<TestnetV0>::from_field(&U64::new(participant_index).to_field().unwrap()).unwrap();
+    let participant_index_scalar = participant_index.to_scalar();
 
     for index in all_participant_indices {
         // Skip the index if it is the same as the participant index.
@@ -23,35 +49,45 @@ pub fn calculate_lagrange_coefficients(
             continue;
         }
 
-        let scalar = Scalar::<TestnetV0>::from_field(&U64::new(*index).to_field().unwrap()).unwrap();
+        let scalar = index.to_scalar();
 
         numerator = numerator * scalar;
         denominator = denominator * (scalar - participant_index_scalar);
     }
-    
-    // skipping below checks on duplicate index or failed to invert denomitor
-    // if denominator == G::ScalarField::zero() {
-    //     return Err(anyhow!("There was a duplicate index"));
-    // }
-
-    // let inverted_denominator = match denominator.inverse() {
-    //     Some(res) => res,
-    //     None => return Err(anyhow!("Failed to invert denominator")),
-    // };
-    
-    let inverted_denominator = denominator.inverse().unwrap();
+
+    let inverted_denominator = denominator.inverse()?;
 
     Ok(numerator * inverted_denominator)
 
 }
 
+/// Evaluate the public commitment to a secret polynomial at a participant's identifier
+///
+/// Returns g^f(id) as Product of C_k^(id^k), using Horner's Method
+pub fn evaluate_commitment(
+    commitment: &[Group<TestnetV0>],
+    participant_index: Identifier,
+) -> Group<TestnetV0> {
+    let index_scalar = participant_index.to_scalar();
+    let mut result = Group::<TestnetV0>::zero();
+    for (i, c) in commitment.iter().rev().enumerate() {
+        result = result + c;
+
+        if i != commitment.len() - 1 {
+            result = result * index_scalar;
+        }
+    }
+
+    result
+}
+
 /// Generating the binding value -- rho_i -- that ensures signature is unique for a particular 
 /// signing set, set of commitments, and message
 /// rho_i = H1(index, H(m), B)
 /// 
 /// Implemented their way by hashing message
 pub fn calculate_binding_value(
-    participant_index: u64,
+    participant_index: Identifier,
     signing_commitments: &[SigningCommitment],
     message: &Vec<Field<TestnetV0>>,
 ) -> Scalar<TestnetV0> {
@@ -60,13 +96,13 @@ pub fn calculate_binding_value(
 
     let mut preimage = Vec::new();
     // Skipping adding string of FROST_SHA256 as field to preimage
-    // added new line for participant_index_field to explicitly set network to TestnetV0
-    let participant_index_field: Field<TestnetV0> = U64::new(participant_index).to_field().unwrap();
+    // the participant identifier is hashed as the field element of its scalar
+    let participant_index_field: Field<TestnetV0> = participant_index.to_field().unwrap();
     preimage.push(participant_index_field);
     preimage.push(message_hash);
 
     for commitment in signing_commitments {
-        let commitment_participant_index: Field<TestnetV0> = U64::new(commitment.participant_index).to_field().unwrap();
+        let commitment_participant_index: Field<TestnetV0> = commitment.participant_index.to_field().unwrap();
         preimage.push(commitment_participant_index);
         // the below two had to_x_coordinate and I'm unsure why....
         preimage.push(commitment.hiding.to_x_coordinate());
@@ -87,7 +123,7 @@ pub fn calculate_binding_value(
 /// The only items published are challenge, response, and compute key
 pub fn calculate_group_commitment(
     signing_commitments: &[SigningCommitment],
-    binding_values: &HashMap<u64, Scalar<TestnetV0>>,
+    binding_values: &HashMap<Identifier, Scalar<TestnetV0>>,
 ) -> Group<TestnetV0> {
     // Need to figure out if no to_projective issue -- see OG code commented out below
     // let mut accumulator = G::zero().to_projective();
@@ -114,3 +150,30 @@ pub fn calculate_group_commitment(
     
 }
 
+/// Replace the file at the path with secret contents, durably and readable by the owner only
+///
+/// The contents are written to a temporary file created with mode 0600 and synced before it is
+/// renamed over the file, and the directory is synced after the rename, so after a crash the file
+/// holds either the old or the new contents and an erased secret cannot come back.
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let temporary = path.with_extension("tmp");
+    // A temporary file left by a crash may have other permissions
+    let _ = std::fs::remove_file(&temporary);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&temporary)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&temporary, path)?;
+
+    let directory = match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+    File::open(directory)?.sync_all()?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// Helpers shared by the tests running the daemons on localhost
+use std::{
+    collections::HashMap,
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command},
+    thread,
+    time::Duration,
+};
+
+use snarkvm_console_account::{private_key::*, Address};
+use snarkvm_console_network::TestnetV0;
+use snarkvm_console_types_scalar::TestRng;
+
+use aleo_frost::*;
+
+// The coordinator process, killed when the test ends
+pub struct CoordinatorProcess {
+    child: Child,
+    pub url: String,
+    pub state_dir: PathBuf,
+}
+
+impl CoordinatorProcess {
+    pub fn start(identity_key: &PrivateKey<TestnetV0>, name: &str) -> Self {
+        // Pick a free port for the coordinator to listen on
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let address = format!("127.0.0.1:{port}");
+
+        let state_dir = std::env::temp_dir().join(format!("aleo-frost-{name}-{port}"));
+        std::fs::create_dir_all(&state_dir).unwrap();
+
+        let child = Command::new(env!("CARGO_BIN_EXE_coordinator"))
+            .args([identity_key.to_string(), address.clone(), state_dir.join("audit.jsonl").display().to_string()])
+            .spawn()
+            .unwrap();
+        for _ in 0..100 {
+            if TcpStream::connect(&address).is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        Self { child, url: format!("http://{address}"), state_dir }
+    }
+
+    pub fn get(&self, path: &str) -> (u16, String) {
+        reply(ureq::get(&format!("{}/{path}", self.url)).call())
+    }
+
+    pub fn post(&self, path: &str, body: &[u8]) -> (u16, String) {
+        reply(ureq::post(&format!("{}/{path}", self.url)).set("Content-Type", "application/json").send_bytes(body))
+    }
+}
+
+impl Drop for CoordinatorProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.state_dir);
+    }
+}
+
+fn reply(response: Result<ureq::Response, ureq::Error>) -> (u16, String) {
+    match response {
+        Ok(response) => (response.status(), response.into_string().unwrap()),
+        Err(ureq::Error::Status(status, response)) => (status, response.into_string().unwrap()),
+        Err(error) => panic!("request failed: {error}"),
+    }
+}
+
+// A 2-of-3 group with an identity key for every party
+pub struct Group {
+    pub account: ThresholdAccount,
+    pub shares: Vec<SignerShare>,
+    pub coordinator_key: PrivateKey<TestnetV0>,
+    pub identity_keys: HashMap<Identifier, PrivateKey<TestnetV0>>,
+    pub config: GroupConfig,
+}
+
+impl Group {
+    pub fn new(rng: &mut TestRng) -> Self {
+        let private_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let (shares, public_keys) = trusted_keygen(3, 2, &private_key.sk_sig(), rng).unwrap();
+        let account = ThresholdAccount::from_private_key(public_keys, &private_key).unwrap();
+
+        let coordinator_key = PrivateKey::<TestnetV0>::new(rng).unwrap();
+        let identity_keys: HashMap<Identifier, PrivateKey<TestnetV0>> =
+            shares.iter().map(|share| (share.participant_index, PrivateKey::new(rng).unwrap())).collect();
+        let config = GroupConfig {
+            coordinator: Address::try_from(&coordinator_key).unwrap(),
+            participants: identity_keys.iter().map(|(index, key)| (*index, Address::try_from(key).unwrap())).collect(),
+        };
+
+        Self { account, shares, coordinator_key, identity_keys, config }
+    }
+
+    // A group registration signed by the coordinator's identity key
+    pub fn registration(&self, group_id: &str, rng: &mut TestRng) -> Vec<u8> {
+        let registration = GroupRegistration { group_id: group_id.to_string(), account: self.account.clone(), config: self.config.clone() };
+        serde_json::to_vec(&OperatorRequest::sign(&registration, &self.coordinator_key, rng).unwrap()).unwrap()
+    }
+
+    // A session opening for a message signed by the coordinator's identity key
+    pub fn open_session(&self, session_id: &str, group_id: &str, signers: Vec<Identifier>, message: &SigningMessage, rng: &mut TestRng) -> Vec<u8> {
+        let open = OpenSession {
+            session_id: session_id.to_string(),
+            group_id: group_id.to_string(),
+            signers,
+            timeout_seconds: 600,
+            target: SigningTarget::Message(message.clone()),
+        };
+        serde_json::to_vec(&OperatorRequest::sign(&open, &self.coordinator_key, rng).unwrap()).unwrap()
+    }
+}
@@ -0,0 +1,101 @@
+// Runs the coordinator binary on localhost and drives a signing session over HTTP
+mod common;
+
+use snarkvm_console_account::private_key::*;
+use snarkvm_console_types_scalar::TestRng;
+
+use aleo_frost::*;
+
+use common::*;
+
+#[test]
+fn test_message_session_over_http() {
+    let rng = &mut TestRng::default();
+    let group = Group::new(rng);
+    let coordinator = CoordinatorProcess::start(&group.coordinator_key, "coordinator-session");
+
+    assert_eq!(coordinator.post("groups", &group.registration("group-1", rng)).0, 201);
+
+    let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+    let signers = [&group.shares[0], &group.shares[2]];
+    let signer_indexes = signers.iter().map(|share| share.participant_index).collect();
+    assert_eq!(coordinator.post("sessions", &group.open_session("session-1", "group-1", signer_indexes, &message, rng)).0, 201);
+
+    // Every signer announces its commitment
+    let mut sessions = Vec::new();
+    for share in signers {
+        let path = coordinator.state_dir.join(format!("signer-{}.json", share.participant_index));
+        let mut session = SignerSession::create(path, "session-1", share.participant_index, 600, rng).unwrap();
+        let signed = SignedEnvelope::sign(&session.announce().unwrap(), &group.identity_keys[&share.participant_index], rng).unwrap();
+        assert_eq!(coordinator.post("sessions/session-1/commitments", &signed.encode().unwrap()).0, 200);
+        sessions.push(session);
+    }
+
+    // Every signer signs the package sent by the coordinator
+    let (status, body) = coordinator.get("sessions/session-1/package");
+    assert_eq!(status, 200);
+    let package = serde_json::from_str::<SignedEnvelope>(&body).unwrap().open(&group.config).unwrap();
+    for (share, session) in signers.iter().zip(&mut sessions) {
+        let request = session.receive(&package).unwrap().unwrap();
+        let mut ledger = SpendingLedger::open(coordinator.state_dir.join(format!("spending-{}.json", share.participant_index))).unwrap();
+        let envelope = session.sign(&request, &group.account, share, &SigningPolicy::default(), &mut ledger).unwrap();
+        let signed = SignedEnvelope::sign(&envelope, &group.identity_keys[&share.participant_index], rng).unwrap();
+        assert_eq!(coordinator.post("sessions/session-1/partials", &signed.encode().unwrap()).0, 200);
+    }
+
+    let (status, body) = coordinator.get("sessions/session-1/result");
+    assert_eq!(status, 200);
+    match serde_json::from_str::<SignedEnvelope>(&body).unwrap().open(&group.config).unwrap().payload {
+        Payload::AggregationResult(result) => match *result {
+            AggregationResult::Signature(signature) => {
+                assert!(signature.verify(&group.account.address().unwrap(), &message.to_fields().unwrap()))
+            }
+            result => panic!("Expected a signature, found {result:?}"),
+        },
+        payload => panic!("Expected the aggregation result, found a {}", payload.name()),
+    }
+
+    // The coordinator recorded the session in its audit log
+    let entries = read_audit_log(coordinator.state_dir.join("audit.jsonl")).unwrap();
+    verify_audit_log(&entries, &group.config.coordinator, None).unwrap();
+    assert!(entries.iter().any(|entry| entry.record.event.kind == AuditEventKind::Aggregation && entry.record.event.outcome == AuditOutcome::Success));
+}
+
+#[test]
+fn test_status_codes() {
+    let rng = &mut TestRng::default();
+    let group = Group::new(rng);
+    let coordinator = CoordinatorProcess::start(&group.coordinator_key, "coordinator-status");
+
+    let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+    let signers: Vec<Identifier> = group.shares[..2].iter().map(|share| share.participant_index).collect();
+
+    // Registrations and sessions not signed by the coordinator's identity key
+    let forged = Group { coordinator_key: PrivateKey::new(rng).unwrap(), ..Group::new(rng) };
+    assert_eq!(coordinator.post("groups", &forged.registration("group-1", rng)).0, 401);
+    let unsigned = GroupRegistration { group_id: "group-1".to_string(), account: group.account.clone(), config: group.config.clone() };
+    assert_eq!(coordinator.post("groups", &serde_json::to_vec(&unsigned).unwrap()).0, 400);
+
+    // Unknown groups and sessions
+    assert_eq!(coordinator.post("sessions", &group.open_session("session-1", "group-1", signers.clone(), &message, rng)).0, 404);
+    assert_eq!(coordinator.get("sessions/session-1/messages").0, 404);
+    assert_eq!(coordinator.get("sessions/session-1/result").0, 404);
+    assert_eq!(coordinator.get("no/such/route").0, 404);
+
+    // Duplicates
+    assert_eq!(coordinator.post("groups", &group.registration("group-1", rng)).0, 201);
+    assert_eq!(coordinator.post("groups", &group.registration("group-1", rng)).0, 409);
+    assert_eq!(coordinator.post("sessions", &group.open_session("session-1", "group-1", signers.clone(), &message, rng)).0, 201);
+    assert_eq!(coordinator.post("sessions", &group.open_session("session-1", "group-1", signers.clone(), &message, rng)).0, 409);
+    assert_eq!(coordinator.post("sessions", &forged.open_session("session-2", "group-1", signers.clone(), &message, rng)).0, 401);
+
+    // No result yet, and a malformed body
+    assert_eq!(coordinator.get("sessions/session-1/result").0, 404);
+    assert_eq!(coordinator.post("sessions", b"{}").0, 400);
+
+    // An envelope signed with another key than the participant's identity key
+    let path = coordinator.state_dir.join("signer.json");
+    let mut session = SignerSession::create(path, "session-1", signers[0], 600, rng).unwrap();
+    let signed = SignedEnvelope::sign(&session.announce().unwrap(), &PrivateKey::new(rng).unwrap(), rng).unwrap();
+    assert_eq!(coordinator.post("sessions/session-1/commitments", &signed.encode().unwrap()).0, 401);
+}
@@ -0,0 +1,139 @@
+// Runs the coordinator and two signer daemons on localhost and signs a message end to end
+mod common;
+
+use std::{
+    path::Path,
+    process::{Child, Command},
+    thread,
+    time::Duration,
+};
+
+use snarkvm_console_account::private_key::*;
+use snarkvm_console_types_scalar::TestRng;
+
+use aleo_frost::*;
+
+use common::*;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+// Cheap cost parameters, the default ones take seconds in debug builds
+const TEST_KDF: KdfParams = KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 };
+
+// A signer daemon, killed when the test ends
+struct SignerProcess {
+    child: Child,
+}
+
+impl SignerProcess {
+    // Write the signer's keystore, policy and config under the directory and start the daemon
+    fn start(group: &Group, share: &SignerShare, coordinator_url: &str, dir: &Path, rng: &mut TestRng) -> Self {
+        std::fs::create_dir_all(dir).unwrap();
+
+        let secrets = SignerSecrets { share: share.clone(), identity_key: group.identity_keys[&share.participant_index] };
+        Keystore::encrypt_with_params(&secrets, PASSWORD, TEST_KDF, rng).unwrap().save(dir.join("keystore.json")).unwrap();
+        SigningPolicy::default().save(dir.join("policy.json")).unwrap();
+
+        let config = serde_json::json!({
+            "coordinator_url": coordinator_url,
+            "account": group.account,
+            "group": group.config,
+            "keystore": dir.join("keystore.json"),
+            "policy": dir.join("policy.json"),
+            "state_dir": dir.join("state"),
+            "nonce_pool_size": 2,
+            "poll_seconds": 1,
+        });
+        std::fs::write(dir.join("config.json"), serde_json::to_string_pretty(&config).unwrap()).unwrap();
+
+        let child = Command::new(env!("CARGO_BIN_EXE_signer"))
+            .arg(dir.join("config.json"))
+            .env("ALEO_FROST_PASSWORD", PASSWORD)
+            .spawn()
+            .unwrap();
+
+        Self { child }
+    }
+}
+
+impl Drop for SignerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_daemons_sign_a_message() {
+    let rng = &mut TestRng::default();
+    let group = Group::new(rng);
+    let coordinator = CoordinatorProcess::start(&group.coordinator_key, "signer-daemons");
+
+    assert_eq!(coordinator.post("groups", &group.registration("group-1", rng)).0, 201);
+
+    let message = SigningMessage::parse_value("{ amount: 10u64 }").unwrap();
+    let signers = [&group.shares[0], &group.shares[1]];
+    let signer_indexes = signers.iter().map(|share| share.participant_index).collect();
+    assert_eq!(coordinator.post("sessions", &group.open_session("session-1", "group-1", signer_indexes, &message, rng)).0, 201);
+
+    let _daemons: Vec<SignerProcess> = signers
+        .iter()
+        .map(|share| {
+            let dir = coordinator.state_dir.join(format!("signer-{}", share.participant_index));
+            SignerProcess::start(&group, share, &coordinator.url, &dir, rng)
+        })
+        .collect();
+
+    // The daemons commit, receive the package and sign it on their own
+    let mut result = None;
+    for _ in 0..120 {
+        let (status, body) = coordinator.get("sessions/session-1/result");
+        if status == 200 {
+            result = Some(body);
+            break;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    let body = result.expect("the daemons did not complete the session");
+
+    match serde_json::from_str::<SignedEnvelope>(&body).unwrap().open(&group.config).unwrap().payload {
+        Payload::AggregationResult(result) => match *result {
+            AggregationResult::Signature(signature) => {
+                assert!(signature.verify(&group.account.address().unwrap(), &message.to_fields().unwrap()))
+            }
+            result => panic!("Expected a signature, found {result:?}"),
+        },
+        payload => panic!("Expected the aggregation result, found a {}", payload.name()),
+    }
+
+    // Each daemon recorded its keygen, commitment and partial signature in its audit log
+    for share in signers {
+        let path = coordinator.state_dir.join(format!("signer-{}", share.participant_index)).join("state").join("audit.jsonl");
+        let entries = read_audit_log(path).unwrap();
+        verify_audit_log(&entries, &group.config.participants[&share.participant_index], None).unwrap();
+        for kind in [AuditEventKind::Keygen, AuditEventKind::Preprocess, AuditEventKind::PartialSignature] {
+            assert!(entries.iter().any(|entry| entry.record.event.kind == kind && entry.record.event.outcome == AuditOutcome::Success));
+        }
+    }
+}
+
+#[test]
+fn test_keystore_holds_the_signer_secrets() {
+    let rng = &mut TestRng::default();
+    let group = Group::new(rng);
+    let share = &group.shares[0];
+    let secrets = SignerSecrets { share: share.clone(), identity_key: group.identity_keys[&share.participant_index] };
+
+    let keystore = Keystore::encrypt_with_params(&secrets, PASSWORD, TEST_KDF, rng).unwrap();
+    assert_eq!(keystore.decrypt(PASSWORD).unwrap(), secrets);
+
+    // A wrong password and a modified ciphertext are both rejected
+    assert!(keystore.decrypt("wrong password").is_err());
+    let mut tampered = keystore.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert!(tampered.decrypt(PASSWORD).is_err());
+
+    // Neither secret is stored in the clear
+    let contents = serde_json::to_string(&keystore).unwrap();
+    assert!(!contents.contains(&secrets.identity_key.to_string()));
+}